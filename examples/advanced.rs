@@ -58,10 +58,10 @@ fn main() {
                 // Failed parsing the config
                 ConfigError::DataParseError(parse_err) => {
                     match parse_err {
-                        DataParseError::Serialize(format) =>
-                            panic!("Failed to serialize format {format}!"),
-                        DataParseError::Deserialize(format, _string) =>
-                            panic!("Failed to deserialize format {format}!")
+                        DataParseError::Serialize(format, error) =>
+                            panic!("Failed to serialize format {format}: {error}"),
+                        DataParseError::Deserialize(format, error) =>
+                            panic!("Failed to deserialize format {format} ({:?}): {}", error.kind, error.message)
                     }
                 }
                 _ => panic!("Other error!")
@@ -7,15 +7,17 @@ cargo build --features json
 cargo build --features json5
 cargo build --features toml
 cargo build --features yaml
-cargo build --features json5,toml,yaml
+cargo build --features ron
+cargo build --features json5,toml,yaml,ron
 
 # Testing the project
 cargo test --features json
 cargo test --features json5
 cargo test --features toml
 cargo test --features yaml
-cargo test --features json,json5,toml,yaml
+cargo test --features ron
+cargo test --features json,json5,toml,yaml,ron
 
 # Enabling advanced (case) testing
 export ADVANCED_TEST=true
-cargo test --features json,json5,toml,yaml
+cargo test --features json,json5,toml,yaml,ron
@@ -0,0 +1,124 @@
+use crate::value::Value;
+use crate::{format_dependant, ConfigFormat};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// A file format that [`Config`](crate::Config) can read and write. <br/>
+/// Implement it to use formats `fast_config` doesn't ship, then add it with [`register_format`].
+///
+/// Formats work with [`Value`]s instead of your data struct,
+/// since `Value` implements [`Serialize`](serde::Serialize) and [`Deserialize`](serde::Deserialize)
+/// you can usually hand it straight to your format's Serde functions.
+///
+/// # Example:
+/// ```
+/// use fast_config::{ConfigFormatBackend, Value};
+///
+/// // A format that stores `key=value` lines
+/// struct KeyValueFormat;
+///
+/// impl ConfigFormatBackend for KeyValueFormat {
+///     fn name(&self) -> &'static str { "kv" }
+///     fn extensions(&self) -> &[&str] { &["kv", "conf"] }
+///     fn supports_pretty(&self) -> bool { false }
+///
+///     fn serialize(&self, value: &Value, _pretty: bool) -> Result<String, String> {
+///         let table = value.as_table().ok_or("Only tables are supported")?;
+///         let mut output = String::new();
+///         for (key, value) in table.iter() {
+///             let value = value.as_str().ok_or("Only strings are supported")?;
+///             output += &format!("{key}={value}\n");
+///         }
+///         Ok(output)
+///     }
+///
+///     fn deserialize(&self, text: &str) -> Result<Value, String> {
+///         let mut table = fast_config::value::Table::new();
+///         for line in text.lines().filter(|line| !line.trim().is_empty()) {
+///             let (key, value) = line.split_once('=').ok_or("Missing `=`")?;
+///             table.insert(key.trim(), Value::String(value.trim().to_string()));
+///         }
+///         Ok(Value::Table(table))
+///     }
+/// }
+///
+/// let format = fast_config::register_format(KeyValueFormat).unwrap();
+/// assert_eq!(fast_config::ConfigFormat::from_extension(std::ffi::OsStr::new("conf")), Some(format));
+/// ```
+pub trait ConfigFormatBackend: Send + Sync {
+    /// The name of the format *(ex: "json")*. <br/>
+    /// It has to be unique, as it's what the format is identified by.
+    fn name(&self) -> &'static str;
+
+    /// The file extensions *(without the dot)* the format gets picked for.
+    fn extensions(&self) -> &[&str];
+
+    /// If the `pretty` option of [`ConfigSetupOptions`](crate::ConfigSetupOptions) changes anything. <br/>
+    /// When it doesn't, [`ConfigFormatBackend::serialize`] always gets `false`. Defaults to `false`.
+    fn supports_pretty(&self) -> bool {
+        false
+    }
+
+    /// Converts a value into the text that gets written to the config file. <br/>
+    /// `pretty` is the `pretty` option of [`ConfigSetupOptions`](crate::ConfigSetupOptions), if the format supports it.
+    fn serialize(&self, value: &Value, pretty: bool) -> Result<String, String>;
+
+    /// Converts the text of a config file into a value.
+    fn deserialize(&self, text: &str) -> Result<Value, String>;
+
+    /// Patches freshly serialized text into the text the config file originally had,
+    /// to keep comments and formatting intact. <br/>
+    /// Returning [`None`] means the freshly serialized text gets written as-is, which is the default.
+    fn merge(&self, original: &str, updated: &str) -> Option<String> {
+        let _ = (original, updated);
+        None
+    }
+
+    /// Finds where the value at `path` *(a list of keys and array indices)* is written in `text`, as a byte offset. <br/>
+    /// Used to report the line and column a value came from, see [`Config::source_of`](crate::Config::source_of).
+    /// Returning [`None`] means the position is unknown, which is the default.
+    fn locate(&self, text: &str, path: &[String]) -> Option<usize> {
+        let _ = (text, path);
+        None
+    }
+}
+
+// Every known format, with the built-in ones first
+static BACKENDS: OnceLock<RwLock<Vec<Arc<dyn ConfigFormatBackend>>>> = OnceLock::new();
+
+fn backends() -> &'static RwLock<Vec<Arc<dyn ConfigFormatBackend>>> {
+    BACKENDS.get_or_init(|| RwLock::new(format_dependant::builtin_backends()))
+}
+
+/// Adds a custom format, so that it can be picked by [`ConfigFormat::from_extension`]
+/// and used by [`Config`](crate::Config). <br/>
+/// Registering a format with the same name as a previous custom format replaces it.
+///
+/// Returns the [`ConfigFormat`] to use in your [`ConfigSetupOptions`](crate::ConfigSetupOptions),
+/// or [`None`] if the name is already used by a built-in format.
+pub fn register_format(backend: impl ConfigFormatBackend + 'static) -> Option<ConfigFormat> {
+    let name = backend.name();
+    if !matches!(ConfigFormat::from_name(name), ConfigFormat::Custom(_)) {
+        return None;
+    }
+
+    let mut backends = backends().write().unwrap_or_else(PoisonError::into_inner);
+    backends.retain(|existing| existing.name() != name);
+    backends.push(Arc::new(backend));
+    Some(ConfigFormat::Custom(name))
+}
+
+/// Gets the backend of a format, if it's enabled/registered
+pub fn get_backend(format: ConfigFormat) -> Option<Arc<dyn ConfigFormatBackend>> {
+    let name = format.to_string();
+    let backends = backends().read().unwrap_or_else(PoisonError::into_inner);
+    backends.iter().find(|backend| backend.name() == name).cloned()
+}
+
+// Gets the first format that uses an extension
+pub(crate) fn find_by_extension(extension: &str) -> Option<ConfigFormat> {
+    let backends = backends().read().unwrap_or_else(PoisonError::into_inner);
+    backends
+        .iter()
+        .find(|backend| backend.extensions().iter().any(|ext| ext.eq_ignore_ascii_case(extension)))
+        .map(|backend| ConfigFormat::from_name(backend.name()))
+}
@@ -0,0 +1,119 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How backup copies of a config file get named.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum BackupNaming {
+    /// `config.toml.1` is the newest backup, `config.toml.2` the one before it, and so on.
+    #[default]
+    Numbered,
+
+    /// Backups are named after the time they were made *(in UTC)*, ex: `config.toml.20240131-235959-123`.
+    Timestamped,
+}
+
+/// Used to configure the backups [`Config::save`](crate::Config::save) makes,
+/// inside of [`ConfigSetupOptions`](crate::ConfigSetupOptions)
+///
+/// # Attributes
+/// - `keep` - How many backups to keep around. Older ones get deleted when a new one is made.
+/// - `naming` - How the backup files get named, see [`BackupNaming`].
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct BackupOptions {
+    pub keep: usize,
+    pub naming: BackupNaming,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        Self {
+            keep: 3,
+            naming: BackupNaming::default(),
+        }
+    }
+}
+
+// Copies the current config file into a new backup, and deletes the backups that are too old
+pub(crate) fn create(path: &Path, options: &BackupOptions) -> io::Result<()> {
+    if options.keep == 0 || !path.is_file() {
+        return Ok(());
+    }
+
+    match options.naming {
+        BackupNaming::Numbered => {
+            // Shifting every backup up by one, dropping the ones past the limit
+            let mut last = options.keep;
+            while numbered_path(path, last + 1).exists() {
+                last += 1;
+            }
+            for n in (options.keep..=last).rev() {
+                remove_if_exists(&numbered_path(path, n))?;
+            }
+            for n in (1..options.keep).rev() {
+                let from = numbered_path(path, n);
+                if from.exists() {
+                    fs::rename(from, numbered_path(path, n + 1))?;
+                }
+            }
+            fs::copy(path, numbered_path(path, 1))?;
+        }
+        BackupNaming::Timestamped => {
+            fs::copy(path, suffixed_path(path, &crate::utils::timestamp()))?;
+            for old in list(path, BackupNaming::Timestamped).into_iter().skip(options.keep) {
+                remove_if_exists(&old)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Lists the existing backups of a config file, newest first
+pub(crate) fn list(path: &Path, naming: BackupNaming) -> Vec<PathBuf> {
+    match naming {
+        BackupNaming::Numbered => (1..)
+            .map(|n| numbered_path(path, n))
+            .take_while(|backup| backup.exists())
+            .collect(),
+        BackupNaming::Timestamped => {
+            let Some(file_name) = path.file_name().map(|name| name.to_string_lossy().to_string()) else {
+                return Vec::new();
+            };
+            let dir = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            let Ok(entries) = fs::read_dir(dir) else {
+                return Vec::new();
+            };
+
+            let prefix = format!("{file_name}.");
+            let mut stamps: Vec<String> = entries
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter_map(|name| name.strip_prefix(&prefix).map(str::to_string))
+                .filter(|stamp| crate::utils::is_timestamp(stamp))
+                .collect();
+            // The timestamps sort chronologically as plain strings
+            stamps.sort_unstable_by(|a, b| b.cmp(a));
+            stamps.iter().map(|stamp| suffixed_path(path, stamp)).collect()
+        }
+    }
+}
+
+fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    suffixed_path(path, &n.to_string())
+}
+
+pub(crate) fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{suffix}"));
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
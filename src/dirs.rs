@@ -0,0 +1,138 @@
+use crate::ConfigFormat;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// - This module finds the directories an app's config files are supposed to go in.
+//   Linux and other unixes follow the XDG base directory spec,
+//   macOS and Windows use their usual application data folders.
+
+/// Gets the directories an app's config files can be in, from the highest to the lowest priority. <br/>
+/// The first one is always the per-user directory, and the rest are system-wide ones.
+///
+/// | Platform      | Per-user                                   | System-wide                                   |
+/// |---------------|--------------------------------------------|-----------------------------------------------|
+/// | Linux / unix  | `$XDG_CONFIG_HOME/<app>` *(`~/.config`)*   | `$XDG_CONFIG_DIRS/<app>` *(`/etc/xdg`)*       |
+/// | macOS         | `~/Library/Application Support/<app>`      | `/Library/Application Support/<app>`          |
+/// | Windows       | `%APPDATA%\<org>\<app>`                    | `%PROGRAMDATA%\<org>\<app>`                   |
+///
+/// `org` is only used on Windows, and skipped if it's empty. <br/>
+/// Returns an empty list if the per-user directory can't be found *(ex: `$HOME` isn't set)*.
+pub fn config_dirs(org: &str, app: &str) -> Vec<PathBuf> {
+    config_dirs_with(org, app, |var| env::var_os(var))
+}
+
+// Same as `config_dirs`, with the environment variables looked up through `var`
+pub(crate) fn config_dirs_with(org: &str, app: &str, var: impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    let Some(user) = user_dir(&var) else {
+        return Vec::new();
+    };
+    let app_dir = |base: PathBuf| match cfg!(windows) && !org.is_empty() {
+        true => base.join(org).join(app),
+        false => base.join(app),
+    };
+
+    let mut dirs = vec![app_dir(user)];
+    dirs.extend(system_dirs(&var).into_iter().map(app_dir));
+    dirs
+}
+
+// Finds the first `<name>.<ext>` file in `dirs` whose extension belongs to a known format.
+// Files in the same directory are picked by name, so the result doesn't depend on the file system
+pub(crate) fn find(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter().find_map(|dir| {
+        let mut found: Vec<PathBuf> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.file_stem().is_some_and(|stem| stem == name)
+                    && path.extension().and_then(ConfigFormat::from_extension).is_some()
+                    && path.is_file()
+            })
+            .collect();
+        found.sort();
+        found.into_iter().next()
+    })
+}
+
+// Gets an absolute path out of an environment variable (relative ones are ignored by the XDG spec)
+fn absolute_var(var: &impl Fn(&str) -> Option<OsString>, name: &str) -> Option<PathBuf> {
+    var(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+#[cfg(windows)]
+fn user_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    absolute_var(var, "APPDATA")
+}
+
+#[cfg(windows)]
+fn system_dirs(var: &impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    absolute_var(var, "PROGRAMDATA").into_iter().collect()
+}
+
+#[cfg(target_os = "macos")]
+fn user_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    absolute_var(var, "HOME").map(|home| home.join("Library/Application Support"))
+}
+
+#[cfg(target_os = "macos")]
+fn system_dirs(_var: &impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    vec![PathBuf::from("/Library/Application Support")]
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn user_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    absolute_var(var, "XDG_CONFIG_HOME").or_else(|| absolute_var(var, "HOME").map(|home| home.join(".config")))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn system_dirs(var: &impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = var("XDG_CONFIG_DIRS")
+        .map(|dirs| env::split_paths(&dirs).filter(|dir| dir.is_absolute()).collect())
+        .unwrap_or_default();
+    match dirs.is_empty() {
+        true => vec![PathBuf::from("/etc/xdg")],
+        false => dirs,
+    }
+}
+
+/// Finds every config file called `name` in `start` and the directories above it, nearest first. <br/>
+/// Looks for `name` with the extension of every enabled format feature *(ex: `.myapp` finds `.myapp.toml` and `.myapp.json`)*.
+///
+/// A relative `start` is taken from the current directory, so that the search can go above it. <br/>
+/// The search stops at the root of the file system, or at the first directory with a `.git` in it
+/// *(the root of a repository)*, whichever comes first. Files in that directory are still included.
+///
+/// See [`Config::discover`](crate::Config::discover) and [`Config::discover_merged`](crate::Config::discover_merged)
+/// to make a config out of them.
+pub fn discover(start: impl AsRef<Path>, name: &str) -> Vec<PathBuf> {
+    let mut extensions: Vec<String> = Vec::new();
+    let backends = crate::format_dependant::get_enabled_features()
+        .into_iter()
+        .filter_map(crate::backend::get_backend);
+    for backend in backends {
+        for extension in backend.extensions() {
+            if !extensions.iter().any(|existing| existing == extension) {
+                extensions.push(extension.to_string());
+            }
+        }
+    }
+
+    // `Path::ancestors` only strips components off the path, so `.` would never go above the current directory
+    let start = std::path::absolute(start.as_ref()).unwrap_or_else(|_| start.as_ref().to_path_buf());
+    let mut found = Vec::new();
+    for dir in start.ancestors() {
+        for extension in &extensions {
+            let path = dir.join(format!("{name}.{extension}"));
+            if path.is_file() {
+                found.push(path);
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    found
+}
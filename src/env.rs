@@ -0,0 +1,175 @@
+use crate::value::{Table, Value};
+use std::ffi::OsString;
+
+// - This module applies environment variable overrides on top of the config data.
+//   A variable such as `MYAPP__DATABASE__PORT` (with the prefix "MYAPP" and the separator "__")
+//   overrides the `port` field of the `database` table.
+// ------------------------------------------------------------------
+// Overrides are applied to a `Value` of the data, and are remembered
+// so that saving can put the original values back before writing.
+
+// A single override that was applied to the data
+#[derive(Debug, Clone)]
+pub(crate) struct Override {
+    /// The name of the environment variable
+    pub var: String,
+    /// The keys leading to the overridden value
+    pub path: Vec<String>,
+    /// The value from the environment variable
+    pub value: Value,
+    /// The value that was there before the override, if there was one
+    pub previous: Option<Value>,
+}
+
+// Applies every matching variable out of `vars` to `root`.
+// Returns the overrides that were applied, or the name of the variable that failed and why
+pub(crate) fn apply(
+    root: &mut Value,
+    prefix: &str,
+    separator: &str,
+    vars: impl IntoIterator<Item = (OsString, OsString)>,
+) -> Result<Vec<Override>, (String, String)> {
+    let start = format!("{prefix}{separator}");
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter_map(|(var, value)| Some((var.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(var, _)| var.get(..start.len()).is_some_and(|var_start| var_start.eq_ignore_ascii_case(&start)))
+        .collect();
+    // Making the order predictable (`A__B` gets applied before `A__B__C`)
+    vars.sort();
+
+    let mut overrides = Vec::new();
+    for (var, raw) in vars {
+        let keys: Vec<&str> = var[start.len()..].split(separator).collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            return Err((var, String::from("The variable name contains an empty key")));
+        }
+        match set(root, &keys, &raw) {
+            Ok((path, value, previous)) => overrides.push(Override { var, path, value, previous }),
+            Err(message) => return Err((var, message)),
+        }
+    }
+    Ok(overrides)
+}
+
+// Every environment variable of the process, which is where overrides come from by default
+pub(crate) fn process_vars() -> Vec<(OsString, OsString)> {
+    std::env::vars_os().collect()
+}
+
+// Puts the values that were there before the overrides back,
+// unless the overridden value got changed since
+pub(crate) fn strip(root: &mut Value, overrides: &[Override]) {
+    for applied in overrides.iter().rev() {
+        let Some((last, parents)) = applied.path.split_last() else {
+            continue;
+        };
+        let Some(table) = table_at(root, parents) else {
+            continue;
+        };
+        if table.get(last) != Some(&applied.value) {
+            continue;
+        }
+        match &applied.previous {
+            Some(previous) => {
+                table.insert(last.clone(), previous.clone());
+            }
+            None => {
+                table.remove(last);
+            }
+        }
+    }
+}
+
+fn table_at<'a>(root: &'a mut Value, keys: &[String]) -> Option<&'a mut Table> {
+    let mut current = root;
+    for key in keys {
+        current = current.get_mut(key)?;
+    }
+    current.as_table_mut()
+}
+
+// Sets the value at `keys`, coercing the raw string into the type of the value that was there
+fn set(root: &mut Value, keys: &[&str], raw: &str) -> Result<(Vec<String>, Value, Option<Value>), String> {
+    let mut path = Vec::with_capacity(keys.len());
+    let mut current = root;
+    for (i, key) in keys.iter().enumerate() {
+        let is_last = i == keys.len() - 1;
+        if current.is_null() {
+            *current = Value::Table(Table::new());
+        }
+        let table = match current {
+            Value::Table(table) => table,
+            other => return Err(format!("\"{}\" is a {}, not a table", path.join("."), other.type_name())),
+        };
+
+        // Environment variables are usually uppercase, so keys are matched case-insensitively
+        let key = table
+            .keys()
+            .find(|existing| existing.eq_ignore_ascii_case(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_lowercase());
+        path.push(key.clone());
+
+        if is_last {
+            let previous = table.get(&key).cloned();
+            let value = coerce(raw, previous.as_ref())?;
+            table.insert(key, value.clone());
+            return Ok((path, value, previous));
+        }
+        if !table.contains_key(&key) {
+            table.insert(key.clone(), Value::Table(Table::new()));
+        }
+        current = table.get_mut(&key).unwrap();
+    }
+    Err(String::from("The variable name has no keys"))
+}
+
+// Converts the text of an environment variable into the same type as `existing`
+fn coerce(raw: &str, existing: Option<&Value>) -> Result<Value, String> {
+    let trimmed = raw.trim();
+    match existing {
+        Some(Value::Bool(_)) => parse_bool(trimmed)
+            .map(Value::Bool)
+            .ok_or_else(|| format!("\"{raw}\" is not a boolean")),
+        Some(Value::Integer(_)) => trimmed
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| format!("\"{raw}\" is not an integer")),
+        Some(Value::Float(_)) => trimmed
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| format!("\"{raw}\" is not a number")),
+        Some(Value::String(_)) => Ok(Value::String(raw.to_string())),
+        Some(Value::Array(items)) => {
+            // Comma-separated lists
+            if trimmed.is_empty() {
+                return Ok(Value::Array(Vec::new()));
+            }
+            trimmed
+                .split(',')
+                .map(|item| coerce(item.trim(), items.first()))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        Some(Value::Table(_)) => Err(String::from(
+            "A whole table can't be overridden, override its fields one by one instead",
+        )),
+        // No type to go off of, so guessing
+        Some(Value::Null) | None => Ok(trimmed
+            .parse()
+            .ok()
+            .map(Value::Bool)
+            .or_else(|| trimmed.parse().ok().map(Value::Integer))
+            .or_else(|| trimmed.parse().ok().map(Value::Float))
+            .unwrap_or_else(|| Value::String(raw.to_string()))),
+    }
+}
+
+fn parse_bool(string: &str) -> Option<bool> {
+    match string.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
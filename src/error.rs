@@ -1,24 +1,138 @@
+use std::ops::Range;
 use std::path::PathBuf;
 #[allow(unused)]
 use serde::de::value::Error;
 use thiserror::Error;
 use crate::ConfigFormat;
+use crate::validate::ValidationError;
 
 // - Some of the display/error traits are implemented with
 //   `thiserror` to save time and source code readability
 // - Other are however implemented manually
 //   inside `error_messages.rs`
 
+/// An error from one of the crates `fast_config` uses *(`serde_json`, `toml`, ..)*,
+/// kept as-is so that it can be reached through [`std::error::Error::source`] and downcast
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// What kind of problem an error is about, see [`ConfigError::kind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+	/// Reading or writing a file failed
+	Io,
+	/// A file isn't valid UTF-8
+	Encoding,
+	/// The format of the config couldn't be found or guessed
+	UnknownFormat,
+	/// The text isn't valid according to its format's syntax *(ex: a missing bracket)*
+	Syntax,
+	/// A value doesn't fit the type of its field *(ex: a string where a number should be)*
+	TypeMismatch,
+	/// A field of your data isn't in the config, and has no default
+	MissingField,
+	/// Your data's `Deserialize` implementation rejected the config for another reason
+	InvalidData,
+	/// Your data couldn't be turned into text
+	Serialize,
+	/// An environment variable override couldn't be applied
+	InvalidOverride,
+	/// An old config file couldn't be upgraded
+	Migration,
+	/// The config has keys your data has no field for
+	UnknownKeys,
+	/// The data failed the checks of its [`Validate`](crate::Validate) implementation
+	Validation,
+	/// The config has no file path to be saved at
+	MissingPath,
+}
+
+impl ErrorKind {
+	// Guesses the kind of a data error from the message Serde gives it
+	pub(crate) fn of_message(message: &str) -> Self {
+		const MISMATCHES: [&str; 4] = ["invalid type: ", "invalid value: ", "invalid length ", "unknown variant `"];
+		if message.contains("missing field `") {
+			ErrorKind::MissingField
+		} else if MISMATCHES.iter().any(|start| message.contains(start)) {
+			ErrorKind::TypeMismatch
+		} else {
+			ErrorKind::InvalidData
+		}
+	}
+}
+
 /// Represents an error related to serialization/deserialization of your data
 #[derive(Debug)]
 pub enum DataParseError {
 	/// Serialization: From an object, to a string (stringification)
-	/// - Stores the format that failed
-	Serialize(ConfigFormat),
+	/// - Stores the format that failed, and the error of its serializer
+	Serialize(ConfigFormat, BoxedError),
 
 	/// Deserialization: From a string, to an object (objectification)
-	/// - Stores the format that failed, as well as the data in string form
-	Deserialize(ConfigFormat, String)
+	/// - Stores the format that failed, as well as why and where it failed
+	Deserialize(ConfigFormat, Box<DeserializeError>)
+}
+
+/// Why and where a config file couldn't be deserialized. <br/>
+/// Use [`DeserializeError::render`] to show the failing line of the file, the same way `rustc` does. <br/>
+/// The parser's own error *(ex: a `toml::de::Error`)* is its [`source`](std::error::Error::source).
+#[derive(Debug)]
+pub struct DeserializeError {
+	/// The error given by the format's parser *(or by your data's `Deserialize` implementation)*
+	pub message: String,
+
+	/// What went wrong: [`ErrorKind::Syntax`], [`ErrorKind::TypeMismatch`], [`ErrorKind::MissingField`]
+	/// or [`ErrorKind::InvalidData`]
+	pub kind: ErrorKind,
+
+	/// The file that failed to be deserialized, if there was one
+	pub path: Option<PathBuf>,
+
+	/// Where the error is in the text, if the format could tell
+	pub span: Option<Span>,
+
+	/// The text that failed to be deserialized, if there was one
+	/// *(layered configs fail after their files get merged, so there isn't a single text to blame)*
+	pub content: Option<String>,
+
+	pub(crate) source: Option<BoxedError>,
+}
+
+impl DeserializeError {
+	// An error somewhere in the text, with its kind guessed from its message
+	pub(crate) fn new(message: String, span: Option<Span>, source: Option<BoxedError>) -> Box<Self> {
+		let kind = ErrorKind::of_message(&message);
+		Box::new(Self { message, kind, path: None, span, content: None, source })
+	}
+}
+
+/// Where an error is in a config file
+/// - `line` and `column` start at 1
+/// - `length` is how many characters the error covers on that line *(at least 1)*
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+	pub line: usize,
+	pub column: usize,
+	pub length: usize,
+}
+
+impl Span {
+	// A span covering a single character
+	#[cfg_attr(not(any(feature = "json", feature = "json5", feature = "yaml", feature = "ron")), allow(dead_code))]
+	pub(crate) fn point(line: usize, column: usize) -> Self {
+		Self { line: line.max(1), column: column.max(1), length: 1 }
+	}
+
+	// A span out of a range of bytes in `text`, cut off at the end of its first line
+	#[cfg_attr(not(feature = "toml"), allow(dead_code))]
+	pub(crate) fn from_range(text: &str, range: Range<usize>) -> Self {
+		let (line, column) = crate::utils::line_column(text, range.start);
+		let length = text
+			.get(range)
+			.and_then(|covered| covered.split('\n').next())
+			.map_or(0, |covered| covered.chars().count());
+		Self { line, column, length: length.max(1) }
+	}
 }
 
 /// Represents an error related to the file format not being able to be found or guessed
@@ -42,8 +156,8 @@ impl UnknownFormatError {
 pub enum ConfigError {
 	/// Occurs when a file isn't composed of valid UTF-8 characters.
 	/// - Stores the path to the erroring file
-	#[error("InvalidFileEncoding: Failed to read file data of \"{:?}\" into a valid UTF-8 string.", .0)]
-	InvalidFileEncoding(std::io::Error, PathBuf),
+	#[error("InvalidFileEncoding: Failed to read file data of \"{:?}\" into a valid UTF-8 string.", .1)]
+	InvalidFileEncoding(#[source] std::io::Error, PathBuf),
 
 	/// Occurs when the file could not be saved due to filesystem-related errors. <br/>
 	/// Usually when one of the parent directories for the config file could not
@@ -63,14 +177,44 @@ pub enum ConfigError {
 	///
 	/// # This error can be avoided by either:
 	/// 1. Adding a file extension at the end of the path name
-	///    (`json`/`json5`, `toml`, `yaml`/`yml`) <br/>
+	///    (`json`/`json5`, `toml`, `yaml`/`yml`, `ron`) <br/>
 	///    *(It would be appreciated if you create an issue on the project's Github if
-	///      you notice an extension type is missing)*
+	///    you notice an extension type is missing)*
 	/// 2. Passing a `ConfigSetupOptions` struct into `Config::from_options`, and defining
 	///    the format there.
 	/// 3. Or only having one enabled `format` feature in your `cargo.toml`
 	#[error(transparent)]
-	UnknownFormat(UnknownFormatError)
+	UnknownFormat(UnknownFormatError),
+
+	/// Occurs when an environment variable override can't be applied to your data
+	/// *(ex: a variable meant for a number field that doesn't contain a number)*
+	/// - Stores the name of the variable, and the reason it couldn't be applied
+	#[error("InvalidOverride: The environment variable \"{0}\" could not be applied: {1}")]
+	InvalidOverride(String, String),
+
+	/// Occurs when an old config file can't be upgraded to the current version of your data
+	/// - Stores the version the file was being migrated from *(`0` if the version couldn't be read)*,
+	///   and the reason it failed
+	#[error("Migration: The config could not be migrated from version {0}: {1}")]
+	Migration(u64, String),
+
+	/// Occurs when the config file has keys your data has no field for *(ex: a typo'd field name)*,
+	/// and unknown keys are set to [`UnknownKeys::Deny`](crate::UnknownKeys::Deny)
+	/// - Stores the dotted paths of the unknown keys
+	#[error("UnknownKeys: The config has keys that don't match any setting: {}", .0.join(", "))]
+	UnknownKeys(Vec<String>),
+
+	/// Occurs when the data fails the checks of its [`Validate`](crate::Validate) implementation
+	/// - Stores every problem that was found
+	#[error("Validation: The config is invalid: {}", crate::validate::describe(.0))]
+	Validation(Vec<ValidationError>),
+
+	/// Occurs when a value can't be read or written by its key path with [`Config::get`](crate::Config::get),
+	/// [`Config::set`](crate::Config::set) or [`Config::remove`](crate::Config::remove)
+	/// *(ex: setting a number field to a string)*
+	/// - Stores the key path, and the reason it failed
+	#[error("InvalidKey: The value at \"{0}\" could not be used: {1}")]
+	InvalidKey(String, String)
 }
 
 impl From<std::io::Error> for ConfigError {
@@ -93,9 +237,67 @@ pub enum ConfigSaveError {
 	IoError(std::io::Error),
 
 	/// Occurs when the save data could not be serialized. <br/>
-	/// - Stores an error in string form explaining why serialization failed.
-	#[error("{}", .0)]
-	SerializationError(String)
+	/// - Stores a [`DataParseError::Serialize`] with the serializer's error in it
+	#[error(transparent)]
+	SerializationError(DataParseError),
+
+	/// Occurs when saving a config that has no backing file,
+	/// such as ones made with `Config::from_str` or `Config::empty`. <br/>
+	/// Set the config's `path` before saving it.
+	#[error("MissingPath: The config has no file path to be saved at.")]
+	MissingPath,
+
+	/// Occurs when the data fails the checks of its [`Validate`](crate::Validate) implementation.
+	/// The file is left as it was.
+	/// - Stores every problem that was found
+	#[error("Validation: The config is invalid: {}", crate::validate::describe(.0))]
+	Validation(Vec<ValidationError>)
+}
+
+impl ConfigError {
+	/// Tells what kind of problem the error is about, without having to match on every variant
+	/// *(ex: telling a syntax error in a file apart from a field with the wrong type)*
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			ConfigError::InvalidFileEncoding(..) => ErrorKind::Encoding,
+			ConfigError::IoError(_) => ErrorKind::Io,
+			ConfigError::DataParseError(error) => error.kind(),
+			ConfigError::UnknownFormat(_) => ErrorKind::UnknownFormat,
+			ConfigError::InvalidOverride(..) => ErrorKind::InvalidOverride,
+			ConfigError::Migration(..) => ErrorKind::Migration,
+			ConfigError::UnknownKeys(_) => ErrorKind::UnknownKeys,
+			ConfigError::Validation(_) => ErrorKind::Validation,
+			ConfigError::InvalidKey(_, message) => ErrorKind::of_message(message),
+		}
+	}
+}
+
+impl DataParseError {
+	/// Tells what kind of problem the error is about
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			DataParseError::Serialize(..) => ErrorKind::Serialize,
+			DataParseError::Deserialize(_, error) => error.kind,
+		}
+	}
+}
+
+impl ConfigSaveError {
+	/// Tells what kind of problem the error is about
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			ConfigSaveError::IoError(_) => ErrorKind::Io,
+			ConfigSaveError::SerializationError(error) => error.kind(),
+			ConfigSaveError::MissingPath => ErrorKind::MissingPath,
+			ConfigSaveError::Validation(_) => ErrorKind::Validation,
+		}
+	}
+}
+
+impl From<DataParseError> for ConfigSaveError {
+	fn from(item: DataParseError) -> Self {
+		ConfigSaveError::SerializationError(item)
+	}
 }
 
 impl From<std::io::Error> for ConfigSaveError {
@@ -1,5 +1,5 @@
 use std::fmt::Formatter;
-use crate::error::{DataParseError, UnknownFormatError};
+use crate::error::{DataParseError, DeserializeError, UnknownFormatError};
 
 // - This module serves as a way to print out useful error messages
 //   for both the end user, and the developer.
@@ -10,12 +10,19 @@ use crate::error::{DataParseError, UnknownFormatError};
 
 
 // Data parsing error
-impl std::error::Error for DataParseError {}
+impl std::error::Error for DataParseError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			DataParseError::Serialize(_format, error) => Some(error.as_ref()),
+			DataParseError::Deserialize(_format, error) => Some(error.as_ref()),
+		}
+	}
+}
 impl std::fmt::Display for DataParseError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match self {
 			// Object to string
-			DataParseError::Serialize(_format) => {
+			DataParseError::Serialize(format, error) => {
 				let tip = {
 					#[cfg(debug_assertions)] {
 						"Your config's data types must all implement Serialize and Deserialize!"
@@ -25,10 +32,11 @@ impl std::fmt::Display for DataParseError {
 					}
 				};
 				write!(f, "Serialization: An error occurred trying to convert the config to a string.\n
+					       [err] The data couldn't be written as {format}: {error}\n
 					       [tip]: {tip}")
 			},
 			// String to object
-			DataParseError::Deserialize(format, _string) => {
+			DataParseError::Deserialize(format, error) => {
 				let tip = {
 					#[cfg(debug_assertions)] {
 						"Make sure your data structs types/names match up with the config file you're trying to read.\n
@@ -43,13 +51,77 @@ impl std::fmt::Display for DataParseError {
 					}
 				};
 				write!(f, "Deserialization: An error occurred trying to convert a string into a config object.\n
-						   [err] Config file isn't valid according to it's format ({format})\n
+						   [err] Config file isn't valid according to it's format ({format}): {error}\n
 						   [tip]: {tip}")
 			}
 		}
 	}
 }
 
+// Where deserializing failed, as `path:line:column: message`
+impl std::error::Error for DeserializeError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		self.source.as_deref().map(|error| error as &(dyn std::error::Error + 'static))
+	}
+}
+impl std::fmt::Display for DeserializeError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		if let Some(location) = self.location() {
+			write!(f, "{location}: ")?;
+		}
+		write!(f, "{}", self.message)
+	}
+}
+
+impl DeserializeError {
+	/// Renders the error along with the line of the file it's on, pointing at it the same way `rustc` does:
+	/// ```text
+	/// error: invalid type: string "yes", expected a boolean
+	///  --> config.toml:3:11
+	///   |
+	/// 3 | enabled = "yes"
+	///   |           ^^^^^
+	/// ```
+	/// Only the first line is there if the format couldn't tell where the error is.
+	pub fn render(&self) -> String {
+		let mut lines = vec![format!("error: {}", self.message)];
+		let (Some(span), Some(content)) = (self.span, &self.content) else {
+			if let Some(location) = self.location() {
+				lines.push(format!(" --> {location}"));
+			}
+			return lines.join("\n");
+		};
+
+		let gutter = " ".repeat(span.line.to_string().len());
+		if let Some(location) = self.location() {
+			lines.push(format!("{gutter}--> {location}"));
+		}
+		if let Some(line) = content.lines().nth(span.line - 1) {
+			// Keeping the tabs, so that the carets line up with the line above
+			let indent: String = line
+				.chars()
+				.take(span.column - 1)
+				.map(|c| if c == '\t' { '\t' } else { ' ' })
+				.collect();
+			lines.push(format!("{gutter} |"));
+			lines.push(format!("{} | {line}", span.line));
+			lines.push(format!("{gutter} | {indent}{}", "^".repeat(span.length)));
+		}
+		lines.join("\n")
+	}
+
+	// `path:line:column`, or as much of it as is known
+	fn location(&self) -> Option<String> {
+		let path = self.path.as_ref().map(|path| path.display().to_string());
+		match (path, self.span) {
+			(Some(path), Some(span)) => Some(format!("{path}:{}:{}", span.line, span.column)),
+			(Some(path), None) => Some(path),
+			(None, Some(span)) => Some(format!("{}:{}", span.line, span.column)),
+			(None, None) => None,
+		}
+	}
+}
+
 // Unknown file format (json, toml, etc)
 impl std::error::Error for UnknownFormatError {}
 impl std::fmt::Display for UnknownFormatError {
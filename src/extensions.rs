@@ -1,18 +1,18 @@
-use std::fmt::Debug;
 use std::result::Result;
+use crate::error::BoxedError;
 
-pub type GenericResult<D> = Result<D, String>;
+pub type GenericResult<D> = Result<D, BoxedError>;
 
-pub trait ResultGeneralize<T, E> where E: Debug {
+pub trait ResultGeneralize<T> {
     fn generalize(self) -> GenericResult<T>;
 }
 
-impl<T, E> ResultGeneralize<T, E> for Result<T, E> where E: Debug {
+// Keeps the original error around (instead of only its message), so that it can be used as a `source`
+impl<T, E> ResultGeneralize<T> for Result<T, E> where E: std::error::Error + Send + Sync + 'static {
     fn generalize(self: Result<T, E>) -> GenericResult<T> {
         match self {
             Ok(value) => Ok(value),
-            Err(error) => Err(format!("{:?}", error))
+            Err(error) => Err(Box::new(error))
         }
     }
 }
-
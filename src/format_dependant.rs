@@ -1,11 +1,11 @@
+use std::sync::Arc;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use crate::{ConfigFormat, InternalOptions};
-use crate::extensions::GenericResult;
-
-// Fixes an unused warning when the user hasn't selected any format
-#[cfg(any(feature = "json", feature = "json5", feature = "toml", feature = "yaml"))]
-use crate::extensions::ResultGeneralize;
+use crate::ConfigFormat;
+use crate::error::{DeserializeError, ErrorKind, Span};
+use crate::backend::{get_backend, ConfigFormatBackend};
+use crate::extensions::{GenericResult, ResultGeneralize};
+use crate::value::Value;
 
 // Getting the enabled features via code
 pub fn get_enabled_features() -> Vec<ConfigFormat> {
@@ -14,6 +14,7 @@ pub fn get_enabled_features() -> Vec<ConfigFormat> {
         #[cfg(feature = "json5")] ConfigFormat::JSON5, 
         #[cfg(feature = "toml")] ConfigFormat::TOML, 
         #[cfg(feature = "yaml")] ConfigFormat::YAML,
+        #[cfg(feature = "ron")] ConfigFormat::RON,
     ]
 }
 
@@ -38,11 +39,11 @@ pub fn get_first_enabled_feature() -> ConfigFormat {
 }
 
 // Creates a new string from an existing data object (Serialization)
-pub fn to_string<D>(value: &D, options: &InternalOptions) -> GenericResult<String> where D: Serialize {
-    match options.format {
+pub fn to_string<D>(value: &D, format: &ConfigFormat, pretty: bool) -> GenericResult<String> where D: Serialize {
+    match format {
         #[cfg(feature = "json")]
         ConfigFormat::JSON => {
-            match options.pretty {
+            match pretty {
                 true => serde_json::to_string_pretty(value).generalize(),
                 false => serde_json::to_string(value).generalize()
             }
@@ -50,12 +51,16 @@ pub fn to_string<D>(value: &D, options: &InternalOptions) -> GenericResult<Strin
         
         #[cfg(feature = "json5")]
         ConfigFormat::JSON5 => {
-            json5::to_string(value).generalize()
+            // JSON5 is a superset of JSON, and the json5 crate can't pretty-print
+            match pretty {
+                true => serde_json::to_string_pretty(value).generalize(),
+                false => json5::to_string(value).generalize()
+            }
         },
 
         #[cfg(feature = "toml")]
         ConfigFormat::TOML => {
-            match options.pretty {
+            match pretty {
                 true => toml::to_string_pretty(value).generalize(),
                 false => toml::to_string(value).generalize()
             }
@@ -63,47 +68,223 @@ pub fn to_string<D>(value: &D, options: &InternalOptions) -> GenericResult<Strin
 
         #[cfg(feature = "yaml")]
         ConfigFormat::YAML => {
-            match options.pretty {
+            match pretty {
                 true  => serde_yml::to_string(value).generalize(),
                 false => {
-                    let string = serde_yml::to_string(value);
-                    if string.is_err() {
-                        return Err(string.err().unwrap().to_string());
-                    }
-                    Ok(crate::utils::compress_string(string.unwrap()))
+                    let string = serde_yml::to_string(value).generalize()?;
+                    Ok(crate::utils::compress_string(string))
                 }
             }
         },
 
+        #[cfg(feature = "ron")]
+        ConfigFormat::RON => {
+            match pretty {
+                true => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).generalize(),
+                false => ron::to_string(value).generalize()
+            }
+        },
+
+        // Custom formats go through a `Value`
+        ConfigFormat::Custom(name) => {
+            let backend = get_backend(*format).ok_or_else(|| format!("The format \"{name}\" was never registered"))?;
+            let pretty = pretty && backend.supports_pretty();
+            Ok(backend.serialize(&crate::value::to_value(value).generalize()?, pretty)?)
+        },
+
         // Note: This is here to stop unused pattern warns/errors
-        #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml")))]
-        _ => Err(format!("Missing feature for format \"{}\". Try enabling it in your Cargo.toml", options.format))
+        #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron")))]
+        _ => Err(format!("Missing feature for format \"{}\". Try enabling it in your Cargo.toml", format).into())
+    }
+}
+
+// Creates a new string from an existing data object, patching it into
+// the original file contents so that comments and formatting are kept
+pub fn to_string_preserving<D>(value: &D, original: &str, format: &ConfigFormat, pretty: bool) -> GenericResult<String> where D: Serialize {
+    let updated = to_string(value, format, pretty)?;
+    let merged = get_backend(*format).and_then(|backend| backend.merge(original, &updated));
+    Ok(merged.unwrap_or(updated))
+}
+
+
+// Creates the format-neutral tree of a string, keeping what the format's own crate forgets about
+// when reading into one (the names of RON enum variants)
+pub fn value_from_string(value: &str, format: &ConfigFormat) -> Result<Value, Box<DeserializeError>> {
+    let parsed = from_string_located(value, format)?;
+    match format {
+        #[cfg(feature = "ron")]
+        ConfigFormat::RON => Ok(crate::ron_value::read(value).unwrap_or(parsed)),
+        _ => Ok(parsed),
     }
 }
 
+// Creates a new data object from a string (Deserialization).
+// Errors tell where in the text they are (if the format's parser says),
+// what kind of error they are, and keep the parser's own error
+pub fn from_string_located<D>(value: &str, format: &ConfigFormat) -> Result<D, Box<DeserializeError>> where D: DeserializeOwned {
+    parse_located(value, format).map_err(|mut error| {
+        // Text that can't even be read without any data in mind has a syntax error in it
+        if parse_located::<serde::de::IgnoredAny>(value, format).is_err() {
+            error.kind = ErrorKind::Syntax;
+        }
+        error
+    })
+}
 
-// Creates a new data object from a string (Deserialization)
-pub fn from_string<D>(value: &str, format: &ConfigFormat) -> GenericResult<D> where D: DeserializeOwned {
+fn parse_located<D>(value: &str, format: &ConfigFormat) -> Result<D, Box<DeserializeError>> where D: DeserializeOwned {
     match format {
         #[cfg(feature = "json")]
         ConfigFormat::JSON =>
-            serde_json::from_str::<D>(value).generalize(),
+            serde_json::from_str::<D>(value).map_err(|e| {
+                let span = (e.line() > 0).then(|| Span::point(e.line(), e.column()));
+                DeserializeError::new(strip_location(e.to_string()), span, Some(Box::new(e)))
+            }),
         
         #[cfg(feature = "json5")]
         ConfigFormat::JSON5 =>
-            json5::from_str::<D>(value).generalize(),
+            json5::from_str::<D>(value).map_err(|e| {
+                let json5::Error::Message { msg, location } = &e;
+                let span = location.as_ref().map(|at| Span::point(at.line, at.column));
+                DeserializeError::new(msg.clone(), span, Some(Box::new(e)))
+            }),
 
         #[cfg(feature = "toml")]
         ConfigFormat::TOML =>
-            toml::from_str::<D>(value).generalize(),
+            toml::from_str::<D>(value).map_err(|e| {
+                let span = e.span().map(|range| Span::from_range(value, range));
+                DeserializeError::new(e.message().to_string(), span, Some(Box::new(e)))
+            }),
 
         #[cfg(feature = "yaml")]
         ConfigFormat::YAML =>
-            serde_yml::from_str::<D>(value).generalize(),
+            serde_yml::from_str::<D>(value).map_err(|e| {
+                let span = e.location().map(|at| Span::point(at.line(), at.column()));
+                DeserializeError::new(strip_location(e.to_string()), span, Some(Box::new(e)))
+            }),
+
+        #[cfg(feature = "ron")]
+        ConfigFormat::RON =>
+            ron::from_str::<D>(value).map_err(|e| {
+                let span = Some(Span::point(e.position.line, e.position.col));
+                let mut error = DeserializeError::new(e.code.to_string(), span, None);
+                // RON has its own errors for what other formats leave to Serde's messages
+                error.kind = match &e.code {
+                    ron::Error::Message(message) => ErrorKind::of_message(message),
+                    ron::Error::MissingStructField { .. } => ErrorKind::MissingField,
+                    ron::Error::NoSuchStructField { .. } | ron::Error::DuplicateStructField { .. } => ErrorKind::InvalidData,
+                    _ => ErrorKind::TypeMismatch,
+                };
+                error.source = Some(Box::new(e));
+                error
+            }),
+
+        // Custom formats go through a `Value`
+        ConfigFormat::Custom(name) => {
+            let backend = get_backend(*format)
+                .ok_or_else(|| DeserializeError::new(format!("The format \"{name}\" was never registered"), None, None))?;
+            let parsed = backend.deserialize(value).map_err(|message| {
+                let mut error = DeserializeError::new(message, None, None);
+                error.kind = ErrorKind::Syntax;
+                error
+            })?;
+            crate::value::from_value::<D>(parsed).map_err(|e| DeserializeError::new(e.to_string(), None, Some(Box::new(e))))
+        },
 
         // Note: This is here to stop unused pattern warns/errors
-        #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml")))]
-        _ => Err(format!("Missing feature for format \"{}\". Try enabling it in your Cargo.toml", format))
+        #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron")))]
+        _ => Err(DeserializeError::new(format!("Missing feature for format \"{}\". Try enabling it in your Cargo.toml", format), None, None))
+    }
+}
+
+// Removes the " at line X column Y" some parsers add to their messages, since it's kept separately
+#[cfg(any(feature = "json", feature = "yaml"))]
+fn strip_location(message: String) -> String {
+    match message.rfind(" at line ") {
+        Some(index) if message[index..].contains(" column ") => message[..index].to_string(),
+        _ => message,
+    }
+}
+
+// The formats that ship with the crate, as backends.
+// When used through `to_string`/`from_string` they skip the `Value` step entirely,
+// keeping format-specific types intact (such as TOML datetimes and RON enums)
+struct BuiltinBackend(ConfigFormat);
+
+pub fn builtin_backends() -> Vec<Arc<dyn ConfigFormatBackend>> {
+    get_enabled_features()
+        .into_iter()
+        .map(|format| Arc::new(BuiltinBackend(format)) as Arc<dyn ConfigFormatBackend>)
+        .collect()
+}
+
+impl ConfigFormatBackend for BuiltinBackend {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            ConfigFormat::JSON => "json",
+            ConfigFormat::JSON5 => "json5",
+            ConfigFormat::TOML => "toml",
+            ConfigFormat::YAML => "yaml",
+            ConfigFormat::RON => "ron",
+            ConfigFormat::Custom(name) => name,
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        match self.0 {
+            ConfigFormat::JSON => &["json"],
+            // JSON5 files often just use the JSON extension
+            ConfigFormat::JSON5 => &["json5", "json"],
+            ConfigFormat::TOML => &["toml"],
+            ConfigFormat::YAML => &["yaml", "yml"],
+            ConfigFormat::RON => &["ron"],
+            ConfigFormat::Custom(_) => &[],
+        }
+    }
+
+    fn supports_pretty(&self) -> bool {
+        true
+    }
+
+    fn serialize(&self, value: &Value, pretty: bool) -> Result<String, String> {
+        to_string(value, &self.0, pretty).map_err(|error| error.to_string())
+    }
+
+    fn deserialize(&self, text: &str) -> Result<Value, String> {
+        value_from_string(text, &self.0).map_err(|error| error.message)
+    }
+
+    #[allow(unused_variables)]
+    fn merge(&self, original: &str, updated: &str) -> Option<String> {
+        match self.0 {
+            #[cfg(feature = "json5")]
+            ConfigFormat::JSON5 => crate::preserve::json5_document::merge(original, updated),
+
+            #[cfg(feature = "toml")]
+            ConfigFormat::TOML => crate::preserve::toml_document::merge(original, updated),
+
+            #[cfg(feature = "yaml")]
+            ConfigFormat::YAML => crate::preserve::yaml_document::merge(original, updated),
+
+            // Formats without a format-preserving patcher
+            _ => None
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn locate(&self, text: &str, path: &[String]) -> Option<usize> {
+        match self.0 {
+            // JSON is a subset of JSON5, so the same parser works for both
+            #[cfg(any(feature = "json", feature = "json5"))]
+            ConfigFormat::JSON | ConfigFormat::JSON5 => crate::preserve::json5_document::locate(text, path),
+
+            #[cfg(feature = "toml")]
+            ConfigFormat::TOML => crate::preserve::toml_document::locate(text, path),
+
+            #[cfg(feature = "yaml")]
+            ConfigFormat::YAML => crate::preserve::yaml_document::locate(text, path),
+
+            _ => None
+        }
     }
 }
 
@@ -0,0 +1,141 @@
+use crate::value::{Table, Value};
+use crate::{error, ConfigFormat};
+use std::path::{Path, PathBuf};
+
+// - This module merges several config files into one set of data.
+//   Every layer overrides the ones before it key by key,
+//   with the last layer being the one that gets saved to.
+// ------------------------------------------------------------------
+// Merging is done on `Value`s, so each layer can use its own format.
+
+/// One of the files a layered [`Config`](crate::Config) gets merged from. <br/>
+/// See [`Config::from_layers`](crate::Config::from_layers).
+///
+/// # Attributes
+/// - `path` - The path to the file. Layers whose file doesn't exist are skipped.
+/// - `format` - The format of the file. When [`None`], it gets guessed from the file's extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub path: PathBuf,
+    pub format: Option<ConfigFormat>,
+}
+
+impl Layer {
+    /// Creates a layer whose format gets guessed from the file extension.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            format: None,
+        }
+    }
+
+    /// Creates a layer with a set format.
+    pub fn with_format(path: impl AsRef<Path>, format: ConfigFormat) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            format: Some(format),
+        }
+    }
+}
+
+// The read-only layers below the writable one
+#[derive(Clone)]
+pub(crate) struct Layers {
+    pub sources: Vec<Layer>,
+    /// The data the config was constructed with
+    pub defaults: Value,
+    /// The defaults, with every read-only layer merged on top
+    pub lower: Value,
+    /// The read-only layers whose file was found, in order
+    pub files: Vec<LayerFile>,
+}
+
+// A read-only layer that was read from the disk
+#[derive(Clone)]
+pub(crate) struct LayerFile {
+    pub path: PathBuf,
+    pub format: ConfigFormat,
+    pub content: String,
+    pub value: Value,
+}
+
+impl Layers {
+    pub fn load(sources: Vec<Layer>, defaults: Value) -> Result<Self, error::ConfigError> {
+        let mut layers = Self {
+            sources,
+            lower: defaults.clone(),
+            defaults,
+            files: Vec::new(),
+        };
+        layers.reload()?;
+        Ok(layers)
+    }
+
+    // Re-reads every read-only layer
+    pub fn reload(&mut self) -> Result<(), error::ConfigError> {
+        let mut lower = self.defaults.clone();
+        let mut files = Vec::new();
+        for layer in &self.sources {
+            if !layer.path.is_file() {
+                continue;
+            }
+
+            let format = match layer.format.or_else(|| layer.path.extension().and_then(ConfigFormat::from_extension)) {
+                Some(format) => format,
+                None => {
+                    return Err(error::ConfigError::UnknownFormat(error::UnknownFormatError::new(
+                        Some(format!("The format of the layer \"{}\" could not be guessed", layer.path.display())),
+                        crate::format_dependant::get_enabled_features(),
+                    )));
+                }
+            };
+            let content = crate::read_text(&layer.path)?;
+            let value = crate::parse_value(&content, &format, &layer.path)?;
+            merge(&mut lower, value.clone());
+            files.push(LayerFile { path: layer.path.clone(), format, content, value });
+        }
+        self.lower = lower;
+        self.files = files;
+        Ok(())
+    }
+}
+
+// Deep-merges `top` into `base`, with `top` winning
+pub(crate) fn merge(base: &mut Value, top: Value) {
+    match (base, top) {
+        (Value::Table(base), Value::Table(top)) => {
+            for (key, value) in top {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top,
+    }
+}
+
+// Gets the parts of `value` that differ from `base`, or `None` if there are none.
+// Tables are compared key by key, anything else is compared as a whole
+pub(crate) fn diff(value: &Value, base: &Value) -> Option<Value> {
+    if value == base {
+        return None;
+    }
+    let (Value::Table(table), Value::Table(base)) = (value, base) else {
+        return Some(value.clone());
+    };
+
+    let mut changed = Table::new();
+    for (key, value) in table.iter() {
+        let difference = match base.get(key) {
+            Some(base_value) => diff(value, base_value),
+            None => Some(value.clone()),
+        };
+        if let Some(difference) = difference {
+            changed.insert(key.clone(), difference);
+        }
+    }
+    (!changed.is_empty()).then_some(Value::Table(changed))
+}
@@ -1,20 +1,38 @@
 #![doc = include_str!("../README.md")]
 
+pub mod backend;
+pub mod backup;
+pub mod dirs;
+mod env;
 pub mod error;
 pub mod error_messages;
 mod extensions;
 mod format_dependant;
+pub mod layers;
+pub mod migrate;
+mod preserve;
+mod schema;
+#[cfg(feature = "ron")]
+mod ron_value;
+#[cfg(any(feature = "ron", feature = "toml"))]
+mod shape;
+pub mod provenance;
+pub mod recovery;
 mod utils;
+pub mod validate;
+pub mod value;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
-#[cfg(not(any(feature = "json", feature = "json5", feature = "toml", feature = "yaml")))]
-compile_error!("You must install at least one format feature: `json`, `json5`, `toml`, or `yaml`");
+#[cfg(not(any(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron")))]
+compile_error!("You must install at least one format feature: `json`, `json5`, `toml`, `yaml`, or `ron`");
 // ^ --- HEY, user! --- ^
 // To do this, you can replace `fast_config = ".."` with
 // `fast_config = { version = "..", features = ["json"] }` in your cargo.toml file.
@@ -27,18 +45,36 @@ mod tests;
 // Separated things
 #[allow(unused)]
 pub use error_messages::*;
+pub use backend::{register_format, ConfigFormatBackend};
+pub use backup::{BackupNaming, BackupOptions};
+pub use layers::Layer;
+pub use migrate::{Migration, Migrations};
+pub use provenance::Source;
+pub use recovery::{Recovered, Recovery};
+pub use validate::{Validate, ValidationError};
+pub use value::Value;
+#[cfg(feature = "watch")]
+pub use watch::ConfigWatcher;
 
 /// Enum used to configure the [`Config`]s file format.
 ///
 /// You can use it in a [`ConfigSetupOptions`], inside [`Config::from_options`]
 ///
 /// ## ⚠️ Make sure to enable the feature flag for a format before using it!
+///
+/// Formats that don't ship with `fast_config` are stored as [`ConfigFormat::Custom`],
+/// see [`ConfigFormatBackend`] on how to add your own. <br/>
+/// More built-in formats may be added later on, so matches on it need a wildcard arm.
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[non_exhaustive]
 pub enum ConfigFormat {
     JSON,
     JSON5,
     TOML,
     YAML,
+    RON,
+    /// A format added through [`register_format`], identified by its name
+    Custom(&'static str),
 }
 
 impl ConfigFormat {
@@ -48,7 +84,7 @@ impl ConfigFormat {
     ///
     /// # Example:
     /// ```
-    /// # use std::ffi::OsStr;
+    /// # use std::ffi::{OsStr, OsString};
     /// # use fast_config::ConfigFormat;
     /// if cfg!(feature = "json") {
     ///     assert_eq!(
@@ -68,17 +104,21 @@ impl ConfigFormat {
             .to_string_lossy()
             .replace('\u{FFFD}', "");
 
-        // Special case for JSON5 since it shares a format with JSON
-        if cfg!(feature = "json5") && !cfg!(feature = "json") && (ext == "json" || ext == "json5") {
-            return Some(ConfigFormat::JSON5);
-        }
-        
-        // Matching
-        match ext.as_str() {
-            "json" => Some(ConfigFormat::JSON),
-            "toml" => Some(ConfigFormat::TOML),
-            "yaml" | "yml" => Some(ConfigFormat::YAML),
-            _ => None,
+        // Built-in formats get checked first, in the same order as the features.
+        // JSON5 also claims the "json" extension, but only gets picked for it if JSON is disabled.
+        backend::find_by_extension(&ext)
+    }
+
+    /// Gets a format by its name *(the same as its [`Display`] output)*.
+    /// Any name that isn't a built-in format becomes a [`ConfigFormat::Custom`].
+    pub(crate) fn from_name(name: &'static str) -> Self {
+        match name {
+            "json" => ConfigFormat::JSON,
+            "json5" => ConfigFormat::JSON5,
+            "toml" => ConfigFormat::TOML,
+            "yaml" => ConfigFormat::YAML,
+            "ron" => ConfigFormat::RON,
+            name => ConfigFormat::Custom(name),
         }
     }
 }
@@ -90,6 +130,8 @@ impl Display for ConfigFormat {
             ConfigFormat::JSON5 => "json5",
             ConfigFormat::TOML => "toml",
             ConfigFormat::YAML => "yaml",
+            ConfigFormat::RON => "ron",
+            ConfigFormat::Custom(name) => name,
         };
         write!(f, "{output}")
     }
@@ -101,6 +143,21 @@ impl Default for ConfigFormat {
     }
 }
 
+/// What to do with keys in the config file that your data has no field for *(ex: a typo'd field name)*. <br/>
+/// Used inside of [`ConfigSetupOptions`], see [`Config::unknown_keys`].
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum UnknownKeys {
+    /// Unknown keys are skipped without being looked for, which is what Serde does on its own.
+    #[default]
+    Ignore,
+
+    /// Unknown keys are logged as warnings, and listed by [`Config::unknown_keys`].
+    Warn,
+
+    /// Unknown keys make loading the config fail with [`ConfigError::UnknownKeys`](error::ConfigError::UnknownKeys).
+    Deny,
+}
+
 /// Used to configure the [`Config`] object
 ///
 /// [`UnknownFormatError`]: error::UnknownFormatError
@@ -117,6 +174,52 @@ impl Default for ConfigFormat {
 ///   based on the file extension and enabled features. <br/>
 ///   If this step fails, an [`UnknownFormatError`] will be returned.
 ///
+/// - `atomic_save` - Makes [`Config::save`] write into a temporary file first, then rename it over the config file.
+///   This way a crash or a full disk can never leave a half-written config behind. <br/>
+///   On by default, only turn it off for filesystems that don't support renaming files.
+///
+/// - `env_prefix` - An [`Option`] containing the prefix of the environment variables that override the config's data.
+///   With the prefix `"MYAPP"`, the variable `MYAPP__DATABASE__PORT` overrides the `port` field inside of the `database` field. <br/>
+///   Keys are matched case-insensitively, and the values get converted into the type of the field they replace
+///   *(lists are comma-separated)*. Overrides are never written into the config file when saving. <br/>
+///   Like the other text options, it has to live as long as the program *(`&'static str`)*,
+///   so a prefix only known at runtime needs to be leaked: `Some(Box::leak(prefix.into_boxed_str()))`.
+///
+/// - `env_separator` - The text separating the keys inside of environment variable names. `"__"` by default.
+///
+/// - `backups` - An [`Option`] containing a [`BackupOptions`].
+///   When set, [`Config::save`] copies the config file into a backup before overwriting it,
+///   keeping the last few versions around. <br/>
+///   See [`Config::restore_backup`] to roll back to one of them.
+///
+/// - `migrations` - An [`Option`] containing the [`Migrations`] that upgrade old config files.
+///   When set, the version of the data is stored in the config file,
+///   and files from older versions are migrated before being deserialized. <br/>
+///   See [`Config::migrated_from`] to know if a file was migrated.
+///
+/// - `write_defaults` - Writes the fields that are missing from an existing config file back into it when it's loaded,
+///   This way, new settings added with `#[serde(default)]` show up in the files of existing users. <br/>
+///   Values that are already in the file are kept as they are, and comments are kept for the formats that support it.
+///   Layered configs never get written to this way. To check the data with [`Validate`] before it gets written,
+///   use [`Config::from_options_validated`].
+///
+/// - `unknown_keys` - What to do with keys in the config file that your data has no field for, see [`UnknownKeys`].
+///   Nothing is done about them by default.
+///
+/// - `schema` - An [`Option`] containing a reference to the JSON Schema of the data *(a URL, or a path relative to the config file)*.
+///   When set, [`Config::save`] links the file to it so that editors can autocomplete and check it:
+///   JSON, JSON5 and YAML files get a `"$schema"` key, and TOML files get a `#:schema` comment on their first line. <br/>
+///   With the `schema` feature, [`Config::write_schema`] can generate the schema next to the config file.
+///
+/// - `recovery` - What to do when the config file can't be deserialized *(ex: a user broke it while editing it)*, see [`Recovery`].
+///   By default the error is returned. Otherwise the file is moved aside, and the config falls back to the default data
+///   *(or keeps every field of the file that still works)*. See [`Config::recovered_from`].
+///
+/// - `lenient` - Makes the fields that fail to deserialize *(ex: `volume = "loud"` where a number is expected)*,
+///   as well as the ones missing from the file, keep their default value, instead of the whole file failing to load. <br/>
+///   Each of them gets logged as a warning, and listed by [`Config::reset_keys`].
+///   The file is left as it is until the config gets saved. Files with syntax errors still fail to load.
+///
 /// # More options are to be added later!
 /// Pass `.. `[`Default::default()`] at the end of your construction
 /// to prevent yourself from getting errors in the future!
@@ -159,6 +262,16 @@ impl Default for ConfigFormat {
 pub struct ConfigSetupOptions {
     pub pretty: bool,
     pub format: Option<ConfigFormat>,
+    pub atomic_save: bool,
+    pub backups: Option<BackupOptions>,
+    pub env_prefix: Option<&'static str>,
+    pub env_separator: &'static str,
+    pub migrations: Option<Migrations>,
+    pub write_defaults: bool,
+    pub unknown_keys: UnknownKeys,
+    pub schema: Option<&'static str>,
+    pub recovery: Recovery,
+    pub lenient: bool,
 
     #[allow(deprecated)]
     #[deprecated(note = "This option can result in I/O during program exit and can potentially corrupt config files!\nUse [`Config::save`] while your program is exiting instead!")]
@@ -171,6 +284,16 @@ impl Default for ConfigSetupOptions {
         Self {
             pretty: true,
             format: None,
+            atomic_save: true,
+            backups: None,
+            env_prefix: None,
+            env_separator: "__",
+            migrations: None,
+            write_defaults: false,
+            unknown_keys: UnknownKeys::Ignore,
+            schema: None,
+            recovery: Recovery::Fail,
+            lenient: false,
             save_on_drop: false,
         }
     }
@@ -181,7 +304,20 @@ impl Default for ConfigSetupOptions {
 pub struct InternalOptions {
     pub pretty: bool,
     pub format: ConfigFormat,
+    pub atomic_save: bool,
+    pub backups: Option<BackupOptions>,
+    pub env_prefix: Option<&'static str>,
+    pub env_separator: &'static str,
+    pub migrations: Option<Migrations>,
+    pub write_defaults: bool,
+    pub unknown_keys: UnknownKeys,
+    pub schema: Option<&'static str>,
+    pub recovery: Recovery,
+    pub lenient: bool,
     pub save_on_drop: bool,
+
+    // Where the environment variable overrides are read from
+    pub(crate) env_vars: fn() -> Vec<(OsString, OsString)>,
 }
 impl TryFrom<ConfigSetupOptions> for InternalOptions {
     /// This function converts a [`ConfigSetupOptions`] into an internally-used [`InternalOptions`].
@@ -202,7 +338,18 @@ impl TryFrom<ConfigSetupOptions> for InternalOptions {
         Ok(Self {
             pretty: options.pretty,
             format,
+            atomic_save: options.atomic_save,
+            backups: options.backups,
+            env_prefix: options.env_prefix,
+            env_separator: options.env_separator,
+            migrations: options.migrations,
+            write_defaults: options.write_defaults,
+            unknown_keys: options.unknown_keys,
+            schema: options.schema,
+            recovery: options.recovery,
+            lenient: options.lenient,
             #[allow(deprecated)] save_on_drop: options.save_on_drop,
+            env_vars: env::process_vars,
         })
     }
 }
@@ -210,7 +357,8 @@ impl TryFrom<ConfigSetupOptions> for InternalOptions {
 /// The main class you use to create/access your configuration files!
 ///
 /// # Construction
-/// See [`Config::new`] and [`Config::from_options`] if you wish to construct a new `Config`!
+/// See [`Config::new`] and [`Config::from_options`] if you wish to construct a new `Config`! <br/>
+/// To create one without a file, see [`Config::from_str`] and [`Config::empty`].
 ///
 /// # Data
 /// This class stores data within a data struct you define yourself.
@@ -248,6 +396,49 @@ where
     pub data: D,
     pub path: PathBuf,
     pub options: InternalOptions,
+
+    // The file contents as they were originally read.
+    // Used to keep comments and formatting intact when saving.
+    document: Option<String>,
+
+    // The environment variable overrides applied to `data`, which never get saved
+    overrides: Vec<env::Override>,
+
+    // The read-only layers below the config file, when using `Config::from_layers`
+    layers: Option<layers::Layers>,
+
+    // Where each value of `data` came from
+    provenance: provenance::Provenance,
+
+    // The version the file was migrated from, if it was
+    migrated_from: Option<u64>,
+
+    // The keys in the file that `data` has no field for (when they're looked for)
+    unknown_keys: Vec<String>,
+
+    // The checks of `D`'s `Validate` implementation, once enabled with `Config::validated`
+    validator: Option<Validator<D>>,
+
+    // How the file was recovered from, if it couldn't be deserialized
+    recovered_from: Option<Recovered>,
+
+    // The default data, which the fields that fail to deserialize fall back to (with the `lenient` option)
+    fallback: Option<Value>,
+
+    // The fields that failed to deserialize and were reset to their defaults
+    reset_keys: Vec<String>,
+}
+
+type Validator<D> = fn(&D) -> Result<(), Vec<ValidationError>>;
+
+// The result of loading the data
+struct Loaded<D> {
+    data: D,
+    overrides: Vec<env::Override>,
+    provenance: provenance::Provenance,
+    migrated_from: Option<u64>,
+    unknown_keys: Vec<String>,
+    reset_keys: Vec<String>,
 }
 
 impl<D> Config<D>
@@ -292,14 +483,412 @@ where
         Self::construct(path, options, data)
     }
 
+    /// Same as [`Config::from_options`], but with the checks of `D`'s [`Validate`] implementation turned on
+    /// *(see [`Config::validated`])*.
+    ///
+    /// The data is checked before anything gets written to the disk,
+    /// so the `write_defaults` option never writes data that fails the checks into the file.
+    pub fn from_options_validated(
+        path: impl AsRef<Path>,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError>
+    where
+        D: Validate,
+    {
+        Self::construct_layered(path, options, data, Vec::new(), Some(D::validate))
+    }
+
+    /// Constructs and returns a new config object from the contents of a config file,
+    /// without reading from or writing to the disk.
+    ///
+    /// - `content`: The text of the config, *(ex: from an embedded resource or a database row)*
+    ///
+    /// - `format`: The [`ConfigFormat`] `content` is written in.
+    ///
+    /// The returned config has no backing file, so [`Config::save`] will fail unless you set its `path` first.
+    /// Use [`Config::to_string`] to get the serialized data back instead.
+    ///
+    /// # Example:
+    /// ```
+    /// # #[cfg(feature = "json")] {
+    /// use fast_config::{Config, ConfigFormat};
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub volume: u8,
+    /// }
+    ///
+    /// let config: Config<MyData> = Config::from_str(r#"{ "volume": 80 }"#, ConfigFormat::JSON).unwrap();
+    /// assert_eq!(config.data.volume, 80);
+    /// # }
+    /// ```
+    pub fn from_str(content: &str, format: ConfigFormat) -> Result<Config<D>, error::ConfigError> {
+        let options = ConfigSetupOptions {
+            format: Some(format),
+            ..Default::default()
+        };
+        let options = Self::resolve_options(None, options)?;
+        let loaded = Self::load(Some(content), Path::new(""), None, None, &options, None)?;
+
+        Ok(Self {
+            data: loaded.data,
+            path: PathBuf::new(),
+            options,
+            document: Some(content.to_string()),
+            overrides: loaded.overrides,
+            layers: None,
+            provenance: loaded.provenance,
+            migrated_from: loaded.migrated_from,
+            unknown_keys: loaded.unknown_keys,
+            validator: None,
+            recovered_from: None,
+            fallback: None,
+            reset_keys: loaded.reset_keys,
+        })
+    }
+
+    /// Constructs and returns a new config object that isn't backed by any file.
+    ///
+    /// - `options`: Takes in a [`ConfigSetupOptions`], same as [`Config::from_options`]. <br/>
+    ///   If there is no `format` selected, the crate will attempt to guess it from one available format `feature`.
+    ///
+    /// - `data`: Takes in a struct that inherits [`Serialize`] and [`Deserialize`]
+    ///
+    /// Nothing is read from the disk. [`Config::save`] will fail unless you set the config's `path` first.
+    pub fn empty(options: ConfigSetupOptions, data: D) -> Result<Config<D>, error::ConfigError> {
+        let options = Self::resolve_options(None, options)?;
+        let loaded = Self::load(None, Path::new(""), Some(data), None, &options, None)?;
+        Ok(Self {
+            data: loaded.data,
+            path: PathBuf::new(),
+            options,
+            document: None,
+            overrides: loaded.overrides,
+            layers: None,
+            provenance: loaded.provenance,
+            migrated_from: loaded.migrated_from,
+            unknown_keys: loaded.unknown_keys,
+            validator: None,
+            recovered_from: None,
+            fallback: None,
+            reset_keys: loaded.reset_keys,
+        })
+    }
+
+    /// Constructs and returns a new config object merged from several files, such as system-wide, user and project configs.
+    ///
+    /// - `layers`: The files to merge, from the lowest to the highest priority. <br/>
+    ///   Each [`Layer`] overrides the ones before it key by key, and can use a different format.
+    ///   Layers whose file doesn't exist are skipped. <br/>
+    ///   The last layer is the config's own file, which is the only one [`Config::save`] writes to.
+    ///   Only the keys that differ from the layers below it get written into it.
+    ///
+    /// - `options`: Takes in a [`ConfigSetupOptions`], same as [`Config::from_options`].
+    ///   Its `format` is used for the last layer if the layer has no format of its own.
+    ///
+    /// - `data`: Takes in a struct that inherits [`Serialize`] and [`Deserialize`],
+    ///   which acts as the lowest layer *(the defaults)*.
+    ///
+    /// # Example:
+    /// ```
+    /// use fast_config::{Config, ConfigSetupOptions, Layer};
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub threads: u32,
+    /// }
+    ///
+    /// let layers = vec![
+    ///     Layer::new("/etc/myapp/config.toml"),
+    ///     Layer::new("./config/myapp.json5"),
+    /// ];
+    /// let config = Config::from_layers(layers, ConfigSetupOptions::default(), MyData { threads: 4 });
+    /// ```
+    pub fn from_layers(
+        mut layers: Vec<Layer>,
+        mut options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        let Some(writable) = layers.pop() else {
+            return Err(error::ConfigError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "A layered config needs at least one layer",
+            )));
+        };
+        options.format = writable.format.or(options.format);
+        Self::construct_layered(writable.path, options, data, layers, None)
+    }
+
+    /// Constructs and returns a new config object stored in the usual config directory of an app,
+    /// using the default options.
+    ///
+    /// - `org`: The name of the organization making the app. Only used on Windows.
+    /// - `app`: The name of the app.
+    /// - `name`: The name of the config file, without an extension.
+    /// - `data`: Takes in a struct that inherits [`Serialize`] and [`Deserialize`]
+    ///
+    /// See [`Config::for_app_with_options`] for how the file is searched for.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// use fast_config::Config;
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub theme: String,
+    /// }
+    ///
+    /// // Ex: ~/.config/myapp/settings.toml on Linux
+    /// let config = Config::for_app("myorg", "myapp", "settings", MyData { theme: "dark".into() }).unwrap();
+    /// ```
+    pub fn for_app(org: &str, app: &str, name: &str, data: D) -> Result<Config<D>, error::ConfigError> {
+        Self::for_app_with_options(org, app, name, ConfigSetupOptions::default(), data)
+    }
+
+    /// Constructs and returns a new config object stored in the usual config directory of an app,
+    /// from a set of custom options.
+    ///
+    /// The directories from [`dirs::config_dirs`] are searched in order,
+    /// for a file called `name` with an extension that [`ConfigFormat::from_extension`] knows.
+    /// - The config's own file is always in the per-user directory, and is where [`Config::save`] writes to.
+    ///   If there is none, its extension is taken from the system-wide file, or else from the `format` in `options`.
+    /// - The first system-wide file that's found gets used as a read-only [`Layer`] below it,
+    ///   so only the settings the user changed end up in their own file.
+    pub fn for_app_with_options(
+        org: &str,
+        app: &str,
+        name: &str,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        Self::from_dirs(dirs::config_dirs(org, app), name, options, data)
+    }
+
+    // Constructs a config out of the directories from `dirs::config_dirs`, highest priority first
+    pub(crate) fn from_dirs(
+        dirs: Vec<PathBuf>,
+        name: &str,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        let Some(user_dir) = dirs.first() else {
+            return Err(error::ConfigError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "The user's config directory could not be found",
+            )));
+        };
+
+        let system = dirs::find(&dirs[1..], name);
+        let path = dirs::find(&dirs[..1], name).unwrap_or_else(|| {
+            let mut path = user_dir.join(name);
+            if let Some(extension) = system.as_ref().and_then(|system| system.extension()) {
+                path.set_extension(extension);
+            }
+            path
+        });
+        Self::construct_layered(path, options, data, system.into_iter().map(Layer::new).collect(), None)
+    }
+
+    /// Constructs and returns a new config object from the nearest project-local config file,
+    /// found by walking up from the `start` directory *(see [`dirs::discover`])*.
+    ///
+    /// - `name`: The name of the config file, without an extension *(ex: `.myapp`)*.
+    ///
+    /// If no file is found, the config goes in `start`,
+    /// with its extension picked from the `format` in `options` *(or from one available format `feature`)*.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// use fast_config::{Config, ConfigSetupOptions};
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub max_width: u32,
+    /// }
+    ///
+    /// // Ex: finds ../../.myapp.toml
+    /// let data = MyData { max_width: 100 };
+    /// let config = Config::discover(".", ".myapp", ConfigSetupOptions::default(), data).unwrap();
+    /// ```
+    pub fn discover(
+        start: impl AsRef<Path>,
+        name: &str,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        let path = dirs::discover(&start, name)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| start.as_ref().join(name));
+        Self::construct(path, options, data)
+    }
+
+    /// Constructs and returns a new config object merged from every project-local config file,
+    /// found by walking up from the `start` directory *(see [`dirs::discover`])*.
+    ///
+    /// - `name`: The name of the config files, without an extension *(ex: `.myapp`)*.
+    ///
+    /// Nearer files override the ones further up, same as with [`Config::from_layers`].
+    /// The nearest file is the one [`Config::save`] writes to. <br/>
+    /// If no file is found, the config goes in `start`, same as with [`Config::discover`].
+    pub fn discover_merged(
+        start: impl AsRef<Path>,
+        name: &str,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        let mut found = dirs::discover(&start, name);
+        if found.is_empty() {
+            return Self::construct(start.as_ref().join(name), options, data);
+        }
+        let nearest = found.remove(0);
+        let lower = found.into_iter().rev().map(Layer::new).collect();
+        Self::construct_layered(nearest, options, data, lower, None)
+    }
+
     // Main, private constructor
     fn construct(
         path: impl AsRef<Path>,
-        mut options: ConfigSetupOptions,
-        mut data: D,
+        options: ConfigSetupOptions,
+        data: D,
+    ) -> Result<Config<D>, error::ConfigError> {
+        Self::construct_layered(path, options, data, Vec::new(), None)
+    }
+
+    fn construct_layered(
+        path: impl AsRef<Path>,
+        options: ConfigSetupOptions,
+        data: D,
+        lower: Vec<Layer>,
+        validator: Option<Validator<D>>,
     ) -> Result<Config<D>, error::ConfigError> {
         let mut path = PathBuf::from(path.as_ref());
+        let options = Self::resolve_options(Some(&path), options)?;
+
+        // Setting the file format
+        if path.extension().is_none() {
+            let extension = backend::get_backend(options.format)
+                .and_then(|backend| backend.extensions().first().map(|ext| ext.to_string()))
+                .unwrap_or_else(|| options.format.to_string());
+            path.set_extension(extension);
+        }
+
+        // Reading from the file if a file was found
+        let mut document = None;
+        if let Ok(mut file) = fs::File::open(&path) {
+            let mut content = String::new();
+            if let Err(err) = file.read_to_string(&mut content) {
+                return Err(error::ConfigError::InvalidFileEncoding(err, path));
+            };
+            document = Some(content);
+        }
+
+        // The default data as a value, for the layers to go on top of and for broken fields to fall back to
+        let defaults = match !lower.is_empty() || options.lenient || options.recovery == Recovery::Salvage {
+            true => Some(value::to_value(&data).map_err(|e| error::DataParseError::Serialize(options.format, Box::new(e)))?),
+            false => None,
+        };
+        let fallback = defaults.clone().filter(|_| options.lenient);
+
+        // Reading the layers below the file
+        let layers = match lower.is_empty() {
+            true => None,
+            false => Some(layers::Layers::load(lower, defaults.clone().unwrap_or_default())?),
+        };
+
+        // Moving the file out of the way if it's broken (and if asked to)
+        let mut recovered_from = None;
+        let loaded = match (&document, options.recovery) {
+            (Some(content), Recovery::Reset | Recovery::Salvage) => {
+                match Self::load(Some(content), &path, None, layers.as_ref(), &options, fallback.as_ref()) {
+                    Ok(loaded) => loaded,
+                    Err(error @ error::ConfigError::DataParseError(error::DataParseError::Deserialize(..))) => {
+                        let (loaded, recovered) = Self::recover(error, content, &path, data, defaults.as_ref(), layers.as_ref(), &options)?;
+                        document = None;
+                        recovered_from = Some(recovered);
+                        loaded
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => Self::load(document.as_deref(), &path, Some(data), layers.as_ref(), &options, fallback.as_ref())?,
+        };
 
+        // Returning the Config object
+
+        let config = Self {
+            data: loaded.data,
+            path,
+            options,
+            document,
+            overrides: loaded.overrides,
+            layers,
+            provenance: loaded.provenance,
+            migrated_from: loaded.migrated_from,
+            unknown_keys: loaded.unknown_keys,
+            validator,
+            recovered_from,
+            fallback,
+            reset_keys: loaded.reset_keys,
+        };
+        // Checking the data before anything gets written
+        config.check(&config.data).map_err(error::ConfigError::Validation)?;
+        if config.options.write_defaults && config.document.is_some() && config.layers.is_none() {
+            config.write_defaults();
+        }
+        Ok(config)
+    }
+
+    // Moves a config file that couldn't be deserialized aside, and loads the data without it
+    // (see `ConfigSetupOptions::recovery`)
+    fn recover(
+        error: error::ConfigError,
+        content: &str,
+        path: &Path,
+        data: D,
+        defaults: Option<&Value>,
+        layers: Option<&layers::Layers>,
+        options: &InternalOptions,
+    ) -> Result<(Loaded<D>, Recovered), error::ConfigError> {
+        let moved_to = recovery::move_aside(path)?;
+        log::warn!("\"{}\" could not be loaded and was moved to \"{}\": {error}", path.display(), moved_to.display());
+
+        // Files that can't even be parsed have nothing to salvage
+        let salvage = options.recovery == Recovery::Salvage && parse::<Value>(content, &options.format, path).is_ok();
+        let loaded = match salvage {
+            true => Self::load(Some(content), path, Some(data), layers, options, defaults)?,
+            false => Self::load(None, path, Some(data), layers, options, None)?,
+        };
+        let reset_keys = loaded.reset_keys.clone();
+        Ok((loaded, Recovered { moved_to, error, reset_keys }))
+    }
+
+    // Writes the fields the file is missing into it (see `ConfigSetupOptions::write_defaults`).
+    // The config already loaded fine at this point, so failing to write only gets logged
+    fn write_defaults(&self) {
+        let missing: Vec<String> = self
+            .sources()
+            .into_iter()
+            .filter(|(_, source)| *source == Source::Default)
+            .map(|(key, _)| key)
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        log::info!("Adding the missing fields {missing:?} to \"{}\"", self.path.display());
+        if let Err(e) = self.save() {
+            log::warn!("Failed to add the missing fields to \"{}\": {e}", self.path.display());
+        }
+    }
+
+    // Picks the format and converts the user options into the internally-used ones
+    fn resolve_options(path: Option<&Path>, mut options: ConfigSetupOptions) -> Result<InternalOptions, error::ConfigError> {
         // Setting up variables
         let enabled_features = format_dependant::get_enabled_features();
         let first_enabled_feature = format_dependant::get_first_enabled_feature();
@@ -315,7 +904,7 @@ where
 
         // Manual format option  >  file extension  >  guessed feature
         if options.format.is_none() {
-            options.format = match path.extension() {
+            options.format = match path.and_then(Path::extension) {
                 Some(extension) => {
                     // - Based on the extension
                     match ConfigFormat::from_extension(extension) {
@@ -331,45 +920,204 @@ where
         }
 
         // Converting the user options into a more convenient internally-used type
-        let options: InternalOptions = match InternalOptions::try_from(options) {
-            Ok(value) => value,
-            Err(message) => {
-                return Err(error::ConfigError::UnknownFormat(
-                    error::UnknownFormatError::new(Some(message), enabled_features),
-                ));
+        match InternalOptions::try_from(options) {
+            Ok(value) => Ok(value),
+            Err(message) => Err(error::ConfigError::UnknownFormat(
+                error::UnknownFormatError::new(Some(message), enabled_features),
+            )),
+        }
+    }
+
+    // Turns the text of the config file into data.
+    // The data gets merged on top of the lower layers (if there are any),
+    // and then the environment variable overrides get applied.
+    // `defaults` is used when there is no text and no layers, `path` is where the text came from.
+    // With a `fallback`, the fields that fail to deserialize fall back to it (or to the layers) instead of failing
+    fn load(
+        content: Option<&str>,
+        path: &Path,
+        defaults: Option<D>,
+        layers: Option<&layers::Layers>,
+        options: &InternalOptions,
+        fallback: Option<&Value>,
+    ) -> Result<Loaded<D>, error::ConfigError> {
+        // Every file the data comes from, to know where each value came from
+        let mut files: Vec<provenance::SourceFile> = layers
+            .iter()
+            .flat_map(|layers| &layers.files)
+            .map(|file| provenance::SourceFile {
+                path: &file.path,
+                format: file.format,
+                content: &file.content,
+                value: Some(file.value.clone()),
+            })
+            .collect();
+
+        // Upgrading the config's own file if it's from an older version
+        let mut migrated_from = None;
+        let mut migrate = |value: &mut Value| -> Result<(), error::ConfigError> {
+            if let Some(migrations) = &options.migrations {
+                let version = migrate::run(value, migrations)?;
+                migrated_from = (version < migrations.current_version()).then_some(version);
             }
+            Ok(())
         };
 
-        // Setting the file format
-        if path.extension().is_none() {
-            path.set_extension(options.format.to_string());
-        }
+        // What the fields that fail to deserialize fall back to, if anything
+        let salvage_base = match (fallback, layers) {
+            (None, _) => None,
+            (Some(_), Some(layers)) => Some(&layers.lower),
+            (Some(fallback), None) => Some(fallback),
+        };
+        let mut reset_keys = Vec::new();
 
-        // Reading from the file if a file was found
-        if let Ok(mut file) = fs::File::open(&path) {
-            let mut content = String::new();
-            if let Err(err) = file.read_to_string(&mut content) {
-                return Err(error::ConfigError::InvalidFileEncoding(err, path));
+        // Looking for unknown keys while turning the value into data, if asked to
+        let tracking = options.unknown_keys != UnknownKeys::Ignore;
+        let mut unknown_keys = Vec::new();
+        let mut to_data = |mut value: Value| {
+            // The reference to the schema isn't part of the data
+            if let Value::Table(table) = &mut value {
+                table.remove(schema::KEY);
+            }
+            if let Some(base) = salvage_base {
+                let top = value;
+                (value, reset_keys) = recovery::salvage::<D>(base.clone(), top.clone());
+                // Fields missing from the file are taken from the layers below when there are some,
+                // but otherwise get reset to their default value just the same
+                if layers.is_none() {
+                    let missing = recovery::missing::<D>(&value, base, &top, &reset_keys);
+                    reset_keys.extend(missing);
+                }
+            }
+            let data = match tracking {
+                true => value::from_value_tracked(value).map(|(data, unknown)| {
+                    unknown_keys = unknown;
+                    data
+                }),
+                false => value::from_value(value),
             };
+            data.map_err(|e| {
+                let mut error = error::DeserializeError::new(e.to_string(), None, Some(Box::new(e)));
+                error.path = file_path(path);
+                error::ConfigError::DataParseError(error::DataParseError::Deserialize(options.format, error))
+            })
+        };
 
-            // Deserialization
-            // (Getting data from a string)
-            if let Ok(value) = format_dependant::from_string(&content, &options.format) {
-                data = value;
-            } else {
-                return Err(error::ConfigError::DataParseError(
-                    error::DataParseError::Deserialize(options.format, content),
-                ));
-            };
+        let data = match (layers, content, defaults) {
+            (None, Some(content), _) => {
+                match options.migrations.is_none() && !tracking && salvage_base.is_none() {
+                    // The tree is only needed for the provenance, so files it can't hold (such as YAML tagged enums)
+                    // still load, straight from the text. That's also where errors point at
+                    true => {
+                        let value = parse_value(content, &options.format, path).ok();
+                        files.push(provenance::SourceFile { path, format: options.format, content, value: value.clone() });
+                        match value.and_then(|value| value::from_value(value).ok()) {
+                            Some(data) => data,
+                            None => parse(content, &options.format, path)?,
+                        }
+                    }
+                    // The file is parsed once, into a tree that both the data and its provenance come from
+                    false => {
+                        let mut value = parse_value(content, &options.format, path)?;
+                        files.push(provenance::SourceFile { path, format: options.format, content, value: Some(value.clone()) });
+                        migrate(&mut value)?;
+                        to_data(value)?
+                    }
+                }
+            }
+            (None, None, Some(defaults)) => defaults,
+            (Some(layers), content, _) => {
+                let mut merged = layers.lower.clone();
+                if let Some(content) = content {
+                    let mut value = parse_value(content, &options.format, path)?;
+                    files.push(provenance::SourceFile { path, format: options.format, content, value: Some(value.clone()) });
+                    migrate(&mut value)?;
+                    layers::merge(&mut merged, value);
+                }
+                to_data(merged)?
+            }
+            (None, None, None) => unreachable!("There is always either a file or default data"),
+        };
+
+        if !unknown_keys.is_empty() {
+            match options.unknown_keys {
+                UnknownKeys::Ignore => {}
+                UnknownKeys::Warn => {
+                    for key in &unknown_keys {
+                        log::warn!("Unknown key \"{key}\" in \"{}\" has no matching setting, and was ignored", path.display());
+                    }
+                }
+                UnknownKeys::Deny => return Err(error::ConfigError::UnknownKeys(unknown_keys)),
+            }
         }
+        for key in &reset_keys {
+            log::warn!("\"{key}\" in \"{}\" is missing or could not be deserialized, and was reset to its default value", path.display());
+        }
+        let (data, overrides) = Self::apply_env(data, options)?;
 
-        // Returning the Config object
+        let loaded = value::to_value(&data).unwrap_or_default();
+        let provenance = provenance::Provenance::new(loaded, &files, &overrides);
+        Ok(Loaded { data, overrides, provenance, migrated_from, unknown_keys, reset_keys })
+    }
 
-        Ok(Self {
-            data,
-            path,
-            options,
-        })
+    // Applies the environment variable overrides (if enabled) on top of the data
+    fn apply_env(data: D, options: &InternalOptions) -> Result<(D, Vec<env::Override>), error::ConfigError> {
+        let Some(prefix) = options.env_prefix else {
+            return Ok((data, Vec::new()));
+        };
+        let to_error = |var: String, message: String| error::ConfigError::InvalidOverride(var, message);
+
+        let mut value = value::to_value(&data).map_err(|e| to_error(prefix.to_string(), e.to_string()))?;
+        let overrides = env::apply(&mut value, prefix, options.env_separator, (options.env_vars)()).map_err(|(var, message)| to_error(var, message))?;
+        if overrides.is_empty() {
+            return Ok((data, overrides));
+        }
+        match value::from_value(value) {
+            Ok(data) => Ok((data, overrides)),
+            // The error doesn't say which variable caused it, so listing all of them
+            Err(e) => {
+                let vars: Vec<&str> = overrides.iter().map(|applied| applied.var.as_str()).collect();
+                Err(to_error(vars.join(", "), e.to_string()))
+            }
+        }
+    }
+
+    /// Turns on the checks of `D`'s [`Validate`] implementation, and runs them on the current data.
+    ///
+    /// From then on, [`Config::reload`] and [`Config::restore_backup`] refuse data that fails them
+    /// *(keeping the current data)*, and [`Config::save`] refuses to write it *(keeping the current file)*.
+    ///
+    /// The checks only run once the config was constructed. With the `write_defaults` option,
+    /// use [`Config::from_options_validated`] instead, so that the data is checked before it gets written.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use fast_config::{Config, Validate, ValidationError};
+    /// # use serde::{Serialize, Deserialize};
+    /// # #[derive(Serialize, Deserialize)]
+    /// # struct MyData { pub port: u32 }
+    /// # impl Validate for MyData {
+    /// #     fn validate(&self) -> Result<(), Vec<ValidationError>> { Ok(()) }
+    /// # }
+    /// let config = Config::new("./config/myconfig.toml", MyData { port: 8080 })
+    ///     .and_then(Config::validated)
+    ///     .unwrap();
+    /// ```
+    pub fn validated(mut self) -> Result<Self, error::ConfigError>
+    where
+        D: Validate,
+    {
+        self.validator = Some(D::validate);
+        self.check(&self.data).map_err(error::ConfigError::Validation)?;
+        Ok(self)
+    }
+
+    // Runs the validation checks, if they're turned on
+    fn check(&self, data: &D) -> Result<(), Vec<ValidationError>> {
+        match self.validator {
+            Some(validate) => validate(data),
+            None => Ok(()),
+        }
     }
 
     /// Saves the config file to the disk.
@@ -379,6 +1127,12 @@ where
     ///
     /// If you wish to specify the path to save it at
     /// you can change the path yourself by setting the Config's `path` property.
+    ///
+    /// If the config was read from an existing file, only the values that changed get written into it,
+    /// so comments and formatting are kept intact for the formats that support it *(JSON5, TOML and YAML)*.
+    ///
+    /// Unless `atomic_save` is turned off in the [`ConfigSetupOptions`], the file is replaced in one step,
+    /// so it never ends up empty or half-written if the program crashes while saving.
     /// <br/> <br/>
     /// ## save_at method
     /// There used to be a built-in function called `save_at` while i was developing the crate,
@@ -387,31 +1141,447 @@ where
     ///
     /// If you'd like this feature to be back feel free to open an issue and I'll add it back right away!
     pub fn save(&self) -> Result<(), error::ConfigSaveError> {
-        let to_string = format_dependant::to_string(&self.data, &self.options);
-        match to_string {
-            // If the conversion was successful
-            Ok(data) => {
-                if let Some(parent_dir) = self.path.parent() {
-                    fs::create_dir_all(parent_dir)?;
-                };
+        if self.path.as_os_str().is_empty() {
+            return Err(error::ConfigSaveError::MissingPath);
+        }
+        self.check(&self.data).map_err(error::ConfigSaveError::Validation)?;
+        let data = self.to_string()?;
 
-                let mut file = fs::File::create(&self.path)?;
+        if let Some(parent_dir) = self.path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        };
 
-                write!(file, "{data}")?;
+        // Keeping the file from before it got migrated, if it's still the one on the disk
+        if let Some(version) = self.migrated_from {
+            if fs::read_to_string(&self.path).ok() == self.document {
+                let mut original = self.path.clone().into_os_string();
+                original.push(format!(".v{version}"));
+                fs::copy(&self.path, original)?;
             }
-            // If the conversion failed
-            Err(e) => {
-                // This error triggering sometimes seems to mean a data type you're using in your
-                // custom data struct isn't supported, but I haven't fully tested it.
-                return Err(error::ConfigSaveError::SerializationError(e));
+        }
+
+        // Backing up the current file (unless nothing changed)
+        if let Some(backups) = &self.options.backups {
+            if fs::read_to_string(&self.path).map_or(true, |current| current != data) {
+                backup::create(&self.path, backups)?;
+            }
+        }
+
+        if self.options.atomic_save {
+            utils::write_atomic(&self.path, &data)?;
+        } else {
+            let mut file = fs::File::create(&self.path)?;
+            write!(file, "{data}")?;
+        }
+        Ok(())
+    }
+
+    /// Serializes the config's data into the text that [`Config::save`] would write,
+    /// without touching the disk.
+    ///
+    /// If the config was read from existing text, comments and formatting are kept the same way `save` keeps them.
+    pub fn to_string(&self) -> Result<String, error::ConfigSaveError> {
+        // TOML has no place for a `$schema` key, so its schema gets referenced in a comment instead
+        let schema_key = self.options.schema.filter(|_| {
+            matches!(self.options.format, ConfigFormat::JSON | ConfigFormat::JSON5 | ConfigFormat::YAML)
+        });
+
+        let typed = self.overrides.is_empty() && self.layers.is_none() && self.options.migrations.is_none() && schema_key.is_none();
+        let to_string = if typed {
+            self.serialize(&self.data)
+        } else {
+            // Environment variable overrides never get written
+            let mut value = value::to_value(&self.data).map_err(|e| self.serialize_error(Box::new(e)))?;
+            env::strip(&mut value, &self.overrides);
+
+            // Only the keys that differ from the layers below get written
+            if let Some(layers) = &self.layers {
+                value = layers::diff(&value, &layers.lower).unwrap_or(Value::Table(value::Table::new()));
+            }
+
+            if let Some(migrations) = &self.options.migrations {
+                value = migrate::stamp(value, migrations);
+            }
+            if let Some(reference) = schema_key {
+                value = schema::embed_key(value, reference);
+            }
+
+            match self.layers.is_some() || self.options.migrations.is_some() || schema_key.is_some() {
+                // A `Value` can't hold RON structs and enums, nor TOML datetimes, so they get written from the data
+                #[cfg(any(feature = "ron", feature = "toml"))]
+                true if matches!(self.options.format, ConfigFormat::RON | ConfigFormat::TOML) => {
+                    let stamp = self.options.migrations.map(|migrations| migrations.key);
+                    self.serialize(&shape::Shaped { data: &self.data, shape: &value, stamp })
+                }
+                true => self.serialize(&value),
+                false => {
+                    let data = value::from_value::<D>(value).map_err(|e| self.serialize_error(Box::new(e)))?;
+                    self.serialize(&data)
+                }
+            }
+        };
+
+        // This error triggering sometimes seems to mean a data type you're using in your
+        // custom data struct isn't supported, but I haven't fully tested it.
+        let text = to_string.map_err(|e| self.serialize_error(e))?;
+        match (self.options.schema, self.options.format) {
+            (Some(reference), ConfigFormat::TOML) => Ok(schema::embed_comment(&text, reference)),
+            _ => Ok(text),
+        }
+    }
+
+    /// Gets the path [`Config::write_schema`] writes the JSON Schema of the data to,
+    /// next to the config file *(ex: `config.toml` -> `config.schema.json`)*.
+    pub fn schema_path(&self) -> PathBuf {
+        schema::path_for(&self.path)
+    }
+
+    /// Generates the JSON Schema of the data. <br/>
+    /// Only available with the `schema` feature, and needs `D` to derive
+    /// [`JsonSchema`](https://docs.rs/schemars/latest/schemars/trait.JsonSchema.html) from the `schemars` crate.
+    #[cfg(feature = "schema")]
+    pub fn json_schema() -> String
+    where
+        D: schemars::JsonSchema,
+    {
+        schema::generate::<D>()
+    }
+
+    /// Writes the JSON Schema of the data to [`Config::schema_path`], returning the path it was written to. <br/>
+    /// Only available with the `schema` feature.
+    ///
+    /// Set the `schema` option to the name of the schema file *(ex: `"config.schema.json"`)*
+    /// to have [`Config::save`] link the config file to it.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// use fast_config::{Config, ConfigSetupOptions};
+    /// use schemars::JsonSchema;
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize, JsonSchema)]
+    /// struct MyData {
+    ///     pub port: u16,
+    /// }
+    ///
+    /// let options = ConfigSetupOptions {
+    ///     schema: Some("myconfig.schema.json"),
+    ///     ..Default::default()
+    /// };
+    /// let config = Config::from_options("./config/myconfig.toml", options, MyData { port: 8080 }).unwrap();
+    /// config.write_schema().unwrap();
+    /// config.save().unwrap();
+    /// ```
+    #[cfg(feature = "schema")]
+    pub fn write_schema(&self) -> Result<PathBuf, error::ConfigSaveError>
+    where
+        D: schemars::JsonSchema,
+    {
+        let path = self.schema_path();
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+        utils::write_atomic(&path, &Self::json_schema())?;
+        Ok(path)
+    }
+
+    // Serializes something in the config's format, patching it into the original file contents if there are any
+    fn serialize<T: Serialize>(&self, data: &T) -> extensions::GenericResult<String> {
+        match &self.document {
+            Some(document) => format_dependant::to_string_preserving(data, document, &self.options.format, self.options.pretty),
+            None => format_dependant::to_string(data, &self.options.format, self.options.pretty),
+        }
+    }
+
+    // Wraps an error that happened while turning the data into text
+    fn serialize_error(&self, error: error::BoxedError) -> error::ConfigSaveError {
+        error::ConfigSaveError::SerializationError(error::DataParseError::Serialize(self.options.format, error))
+    }
+
+    /// Gets the paths of the config file's backups, newest first. <br/>
+    /// The backups are looked up using the `naming` from the `backups` option *(or [`BackupNaming::Numbered`] if it isn't set)*.
+    pub fn backups(&self) -> Vec<PathBuf> {
+        let naming = self.options.backups.map(|backups| backups.naming).unwrap_or_default();
+        backup::list(&self.path, naming)
+    }
+
+    /// Replaces the config's `data` with the contents of one of its backups.
+    ///
+    /// - `n`: Which backup to restore, `1` being the newest one. *(See [`Config::backups`])*
+    ///
+    /// The config file itself isn't touched until you call [`Config::save`].
+    /// If the backup can't be read or parsed, the current `data` is left as it was.
+    pub fn restore_backup(&mut self, n: usize) -> Result<(), error::ConfigError> {
+        let path = match n.checked_sub(1).and_then(|index| self.backups().into_iter().nth(index)) {
+            Some(path) => path,
+            None => {
+                return Err(error::ConfigError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("There is no backup #{n} of \"{}\"", self.path.display()),
+                )));
             }
         };
+
+        let content = read_text(&path)?;
+        let loaded = Self::load(Some(&content), &path, None, self.layers.as_ref(), &self.options, self.fallback.as_ref())?;
+        self.check(&loaded.data).map_err(error::ConfigError::Validation)?;
+        self.data = loaded.data;
+        self.overrides = loaded.overrides;
+        self.provenance = loaded.provenance;
+        self.migrated_from = loaded.migrated_from;
+        self.unknown_keys = loaded.unknown_keys;
+        self.reset_keys = loaded.reset_keys;
+        self.document = Some(content);
         Ok(())
     }
 
-    /// Gets the name of the config file
+    /// Re-reads the config file from the disk, replacing the config's `data` with its contents.
+    ///
+    /// Returns whether `data` actually changed. <br/>
+    /// If the file can't be read or parsed *(or fails [`Config::validated`] checks)*,
+    /// an error is returned and the current `data` is left as it was.
+    pub fn reload(&mut self) -> Result<bool, error::ConfigError> {
+        self.reload_data().map(|(_, changed)| changed)
+    }
+
+    // Reloads the data, returning the old data and whether it changed
+    pub(crate) fn reload_data(&mut self) -> Result<(D, bool), error::ConfigError> {
+        // The config's own file is allowed to be missing if there are layers below it
+        let content = match read_text(&self.path) {
+            Ok(content) => Some(content),
+            Err(error::ConfigError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound && self.layers.is_some() => None,
+            Err(e) => return Err(e),
+        };
+        let mut layers = self.layers.clone();
+        if let Some(layers) = &mut layers {
+            layers.reload()?;
+        }
+        let loaded = Self::load(content.as_deref(), &self.path, None, layers.as_ref(), &self.options, self.fallback.as_ref())?;
+        let Loaded { data, overrides, provenance, migrated_from, unknown_keys, reset_keys } = loaded;
+        self.check(&data).map_err(error::ConfigError::Validation)?;
+
+        // Comparing through `Value`s, since the data doesn't have to implement `PartialEq`
+        let changed = match (value::to_value(&self.data), value::to_value(&data)) {
+            (Ok(old), Ok(new)) => old != new,
+            _ => true,
+        };
+        self.document = content;
+        self.overrides = overrides;
+        self.layers = layers;
+        self.provenance = provenance;
+        self.migrated_from = migrated_from;
+        self.unknown_keys = unknown_keys;
+        self.reset_keys = reset_keys;
+        Ok((std::mem::replace(&mut self.data, data), changed))
+    }
+
+    /// Gets where the value at a dotted key path *(ex: `"database.port"`)* came from. <br/>
+    /// Array items are picked by their index *(ex: `"servers.0.host"`)*.
+    ///
+    /// Returns [`None`] if there is no value at `path`,
+    /// or if it's a table whose values don't all come from the same place.
+    ///
+    /// # Example:
+    /// ```
+    /// # #[cfg(feature = "toml")] {
+    /// use fast_config::{Config, ConfigFormat, Source};
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub port: u16,
+    /// }
+    ///
+    /// let mut config: Config<MyData> = Config::from_str("port = 8080", ConfigFormat::TOML).unwrap();
+    /// assert!(matches!(config.source_of("port"), Some(Source::File { line: Some(1), column: Some(1), .. })));
+    ///
+    /// config.data.port = 80;
+    /// assert_eq!(config.source_of("port"), Some(Source::Programmatic));
+    /// # }
+    /// ```
+    pub fn source_of(&self, path: &str) -> Option<Source> {
+        let current = value::to_value(&self.data).ok()?;
+        self.provenance.source_of(&current, &key_path(path).ok()?)
+    }
+
+    /// Gets where every value of the config came from, as pairs of dotted key paths and [`Source`]s. <br/>
+    /// Handy to dump when debugging where a setting came from.
+    pub fn sources(&self) -> Vec<(String, Source)> {
+        match value::to_value(&self.data) {
+            Ok(current) => self.provenance.all(&current),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Gets the value at a dotted key path *(ex: `"person.name"`)* as a `T`, without having to go through the data's fields. <br/>
+    /// Array items are picked by their index *(ex: `"servers.0.host"`)*.
+    ///
+    /// Returns [`None`] if there is no value at `path`,
+    /// and [`ConfigError::InvalidKey`](error::ConfigError::InvalidKey) if the value isn't a `T`. <br/>
+    /// Every method taking a key path fails with `InvalidKey` if one of its keys is empty *(ex: `""`, `"person..name"`)*.
+    ///
+    /// # Example:
+    /// ```
+    /// # #[cfg(feature = "toml")] {
+    /// use fast_config::{Config, ConfigFormat};
+    /// use serde::{Serialize, Deserialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Person {
+    ///     pub name: String,
+    ///     pub age: u32,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyData {
+    ///     pub person: Person,
+    /// }
+    ///
+    /// let mut config: Config<MyData> = Config::from_str("[person]\nname = 'Joe'\nage = 40", ConfigFormat::TOML).unwrap();
+    /// assert_eq!(config.get::<String>("person.name").unwrap(), Some("Joe".to_string()));
+    ///
+    /// config.set("person.age", 41).unwrap();
+    /// assert_eq!(config.data.person.age, 41);
+    /// assert!(config.set("person.age", "old").is_err());
+    /// # }
+    /// ```
+    pub fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<Option<T>, error::ConfigError> {
+        let current = self.data_value()?;
+        let Some(found) = value::get_at(&current, &key_path(path)?) else {
+            return Ok(None);
+        };
+        value::from_value(found.clone())
+            .map(Some)
+            .map_err(|e| error::ConfigError::InvalidKey(path.to_string(), e.to_string()))
+    }
+
+    /// Sets the value at a dotted key path *(ex: `"person.age"`)*, without having to go through the data's fields. <br/>
+    /// Array items are picked by their index *(ex: `"servers.0.host"`)*. See [`Config::get`] for an example.
+    ///
+    /// The data is left as it was if `new` doesn't fit the field at `path`
+    /// *(failing with [`ConfigError::InvalidKey`](error::ConfigError::InvalidKey))*,
+    /// or if the data has no field at `path` *(failing with [`ConfigError::UnknownKeys`](error::ConfigError::UnknownKeys))*.
+    pub fn set<T: Serialize>(&mut self, path: &str, new: T) -> Result<(), error::ConfigError> {
+        let to_error = |message: String| error::ConfigError::InvalidKey(path.to_string(), message);
+        let new = value::to_value(&new).map_err(|e| to_error(e.to_string()))?;
+        let mut current = self.data_value()?;
+        value::insert_at(&mut current, &key_path(path)?, new).map_err(to_error)?;
+
+        // Values the data has no field for would get silently dropped otherwise
+        let (data, unknown) = value::from_value_tracked::<D>(current).map_err(|e| to_error(e.to_string()))?;
+        if unknown.iter().any(|key| key == path || path.starts_with(&format!("{key}."))) {
+            return Err(error::ConfigError::UnknownKeys(vec![path.to_string()]));
+        }
+        self.data = data;
+        Ok(())
+    }
+
+    /// Removes the value at a dotted key path *(ex: `"person.nickname"`)*, returning whether there was one. <br/>
+    /// Array items are picked by their index *(ex: `"servers.0"`)*, and the items after them get moved down.
+    ///
+    /// Only fields that can be missing from the config *(such as [`Option`]s and fields with `#[serde(default)]`)*
+    /// can be removed, which resets them. Removing any other field fails with
+    /// [`ConfigError::InvalidKey`](error::ConfigError::InvalidKey), leaving the data as it was.
+    pub fn remove(&mut self, path: &str) -> Result<bool, error::ConfigError> {
+        let mut current = self.data_value()?;
+        if value::remove_at(&mut current, &key_path(path)?).is_none() {
+            return Ok(false);
+        }
+        self.data = value::from_value(current)
+            .map_err(|e| error::ConfigError::InvalidKey(path.to_string(), e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Gets the dotted key paths of every value of the config *(ex: `["person.age", "person.name"]`)*. <br/>
+    /// Tables are gone into, while arrays are listed as a single value.
+    pub fn keys(&self) -> Vec<String> {
+        match value::to_value(&self.data) {
+            Ok(current) => value::leaves(&current).into_iter().map(|path| path.join(".")).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // The data as a `Value`
+    fn data_value(&self) -> Result<Value, error::ConfigError> {
+        value::to_value(&self.data)
+            .map_err(|e| error::ConfigError::DataParseError(error::DataParseError::Serialize(self.options.format, Box::new(e))))
+    }
+
+    /// Gets the version the config file was migrated from when it was last read,
+    /// or [`None`] if it was already at the current version *(or there are no `migrations` set)*. <br/>
+    /// See [`Migrations`].
+    pub fn migrated_from(&self) -> Option<u64> {
+        self.migrated_from
+    }
+
+    /// Gets the dotted paths of the keys in the config file that `data` has no field for
+    /// *(ex: `"database.prot"`)*, as of when the file was last read. <br/>
+    /// Always empty unless the `unknown_keys` option is set to [`UnknownKeys::Warn`].
+    pub fn unknown_keys(&self) -> &[String] {
+        &self.unknown_keys
+    }
+
+    /// Gets the dotted paths of the fields that couldn't be deserialized and were reset to their default value
+    /// *(ex: `"audio.volume"` when it's `"loud"` instead of a number, or when it's missing from the file)*,
+    /// as of when the file was last read. <br/>
+    /// Always empty unless the `lenient` option is set *(or the file was salvaged, see [`Recovery::Salvage`])*.
+    pub fn reset_keys(&self) -> &[String] {
+        &self.reset_keys
+    }
+
+    /// Gets how the config file was recovered from, if it couldn't be deserialized when the config was constructed
+    /// and the `recovery` option is set *(see [`Recovery`])*. <br/>
+    /// The recovered data isn't written to the disk until [`Config::save`] is called.
+    /// Files that break later on aren't recovered from by [`Config::reload`], which keeps the current data instead.
+    pub fn recovered_from(&self) -> Option<&Recovered> {
+        self.recovered_from.as_ref()
+    }
+
+    /// Gets the name of the config file *(empty if the config has no backing file)*
     pub fn filename(&self) -> String {
-        self.path.file_name().unwrap().to_string_lossy().to_string()
+        self.path.file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+}
+
+// Deserialization
+// (Getting data from a string)
+pub(crate) fn parse<T: serde::de::DeserializeOwned>(content: &str, format: &ConfigFormat, path: &Path) -> Result<T, error::ConfigError> {
+    format_dependant::from_string_located(content, format).map_err(|error| parse_error(error, content, format, path))
+}
+
+// Same as `parse`, into the format-neutral tree of the file
+pub(crate) fn parse_value(content: &str, format: &ConfigFormat, path: &Path) -> Result<Value, error::ConfigError> {
+    format_dependant::value_from_string(content, format).map_err(|error| parse_error(error, content, format, path))
+}
+
+fn parse_error(mut error: Box<error::DeserializeError>, content: &str, format: &ConfigFormat, path: &Path) -> error::ConfigError {
+    error.path = file_path(path);
+    error.content = Some(content.to_string());
+    error::ConfigError::DataParseError(error::DataParseError::Deserialize(*format, error))
+}
+
+// Splits a dotted key path into its keys, none of which can be empty
+fn key_path(path: &str) -> Result<Vec<String>, error::ConfigError> {
+    let keys: Vec<String> = path.split('.').map(str::to_string).collect();
+    match keys.iter().any(String::is_empty) {
+        true => Err(error::ConfigError::InvalidKey(path.to_string(), "The key path has an empty key in it".to_string())),
+        false => Ok(keys),
+    }
+}
+
+// The path of a config's file, if it has one
+fn file_path(path: &Path) -> Option<PathBuf> {
+    (!path.as_os_str().is_empty()).then(|| path.to_path_buf())
+}
+
+// Reads a whole file into a string
+pub(crate) fn read_text(path: &Path) -> Result<String, error::ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+            Err(error::ConfigError::InvalidFileEncoding(err, path.to_path_buf()))
+        }
+        Err(err) => Err(error::ConfigError::IoError(err)),
     }
 }
 
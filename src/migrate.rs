@@ -0,0 +1,101 @@
+use crate::error::ConfigError;
+use crate::value::{Table, Value};
+
+// - This module upgrades the data of old config files to the current layout of the data,
+//   by running it through a chain of functions (v1 to v2, v2 to v3, ...) before it gets deserialized.
+// ------------------------------------------------------------------
+// The version is stored as a top-level key of the file, and is never part of the data itself.
+
+/// A function that upgrades the data of a config file by one version. <br/>
+/// It works on the file's [`Value`], so that it doesn't have to match the current data struct yet.
+/// Returning an error stops the config from loading.
+pub type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// Used to upgrade old config files, inside of [`ConfigSetupOptions`](crate::ConfigSetupOptions)
+///
+/// # Attributes
+/// - `key` - The top-level key the version of the file is stored in. `"version"` by default.
+/// - `steps` - The migrations, in order. `steps[0]` upgrades version 1 to 2, `steps[1]` upgrades 2 to 3, and so on.
+///   The current version is the number of steps plus one.
+///
+/// Files that have no version in them are treated as version 1. <br/>
+/// Migrated files get rewritten on the next [`Config::save`](crate::Config::save),
+/// with the original kept next to them as `<file>.v<old version>`.
+///
+/// # Example:
+/// ```
+/// use fast_config::{ConfigSetupOptions, Migrations, Value};
+///
+/// // Version 2 renamed `name` into `username`
+/// fn rename_name(value: &mut Value) -> Result<(), String> {
+///     let table = value.as_table_mut().ok_or("The data isn't a table")?;
+///     if let Some(name) = table.remove("name") {
+///         table.insert("username", name);
+///     }
+///     Ok(())
+/// }
+///
+/// let options = ConfigSetupOptions {
+///     migrations: Some(Migrations::new(&[rename_name])),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Migrations {
+    pub key: &'static str,
+    pub steps: &'static [Migration],
+}
+
+impl Migrations {
+    /// Creates migrations stored under the `"version"` key.
+    pub const fn new(steps: &'static [Migration]) -> Self {
+        Self { key: "version", steps }
+    }
+
+    /// The version the data is at once every migration ran.
+    pub fn current_version(&self) -> u64 {
+        self.steps.len() as u64 + 1
+    }
+}
+
+// Upgrades `value` to the current version, taking the version key out of it.
+// Returns the version the value was at before
+pub(crate) fn run(value: &mut Value, migrations: &Migrations) -> Result<u64, ConfigError> {
+    let Value::Table(table) = value else {
+        return Err(ConfigError::Migration(0, format!("The data is a {}, not a table", value.type_name())));
+    };
+    let version = match table.remove(migrations.key) {
+        None => 1,
+        Some(Value::Integer(version)) if version >= 1 => u64::try_from(version).unwrap_or(u64::MAX),
+        Some(other) => {
+            return Err(ConfigError::Migration(0, format!("\"{}\" is not a valid version: {other:?}", migrations.key)));
+        }
+    };
+
+    let current = migrations.current_version();
+    if version > current {
+        return Err(ConfigError::Migration(
+            version,
+            format!("The file is newer than the latest supported version ({current})"),
+        ));
+    }
+    for (from, step) in migrations.steps.iter().enumerate().skip(version as usize - 1) {
+        step(value).map_err(|message| ConfigError::Migration(from as u64 + 1, message))?;
+    }
+    Ok(version)
+}
+
+// Puts the current version in front of the other keys of `value`
+pub(crate) fn stamp(value: Value, migrations: &Migrations) -> Value {
+    let Value::Table(table) = value else {
+        return value;
+    };
+    let mut stamped = Table::new();
+    stamped.insert(migrations.key, Value::Integer(i128::from(migrations.current_version())));
+    for (key, value) in table {
+        if key != migrations.key {
+            stamped.insert(key, value);
+        }
+    }
+    Value::Table(stamped)
+}
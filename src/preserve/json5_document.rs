@@ -0,0 +1,427 @@
+// Only `locate` is used when the `json5` feature is disabled
+#![cfg_attr(not(feature = "json5"), allow(dead_code))]
+
+use serde_json::{Map, Value};
+use std::ops::Range;
+
+/// Patches the values found in `updated` into the `original` JSON5 document. <br/>
+/// Comments, trailing commas, unquoted keys and whitespace of the original document are kept.
+///
+/// Returns [`None`] if the original document couldn't be patched.
+#[cfg(feature = "json5")]
+pub fn merge(original: &str, updated: &str) -> Option<String> {
+    let original_value: Value = json5::from_str(original).ok()?;
+    let updated_value: Value = json5::from_str(updated).ok()?;
+
+    let mut parser = Parser { text: original, position: 0 };
+    let root = parser.parse_value()?;
+
+    let mut edits = Vec::new();
+    merge_node(original, &root, &original_value, &updated_value, &mut edits)?;
+
+    // Applying from the end so the offsets stay valid
+    edits.sort_by_key(|(range, _): &(Range<usize>, String)| std::cmp::Reverse(range.start));
+    let mut merged = original.to_string();
+    for (range, text) in edits {
+        merged.replace_range(range, &text);
+    }
+
+    // Making sure the patched document actually contains the new data
+    match json5::from_str::<Value>(&merged) {
+        Ok(value) if value == updated_value => Some(merged),
+        _ => None,
+    }
+}
+
+/// Finds the offset of the key (or array item) at `path` in a JSON5 *(or JSON)* document.
+pub fn locate(text: &str, path: &[String]) -> Option<usize> {
+    let mut parser = Parser { text, position: 0 };
+    let mut node = parser.parse_value()?;
+    let mut offset = None;
+    for segment in path {
+        let (start, next) = match node.kind {
+            NodeKind::Object(members) => {
+                let member = members.into_iter().rev().find(|member| &member.key == segment)?;
+                (member.start, member.value)
+            }
+            NodeKind::Array(elements) => {
+                let element = elements.into_iter().nth(segment.parse().ok()?)?;
+                (element.value.span.start, element.value)
+            }
+            NodeKind::Scalar => return None,
+        };
+        offset = Some(start);
+        node = next;
+    }
+    offset
+}
+
+enum NodeKind {
+    Object(Vec<Member>),
+    Array(Vec<Element>),
+    Scalar,
+}
+
+struct Node {
+    kind: NodeKind,
+    span: Range<usize>,
+}
+
+struct Member {
+    key: String,
+    /// Whether the key was written without quotes
+    bare: bool,
+    start: usize,
+    value: Node,
+    comma: Option<usize>,
+}
+
+struct Element {
+    value: Node,
+    comma: Option<usize>,
+}
+
+// Something that sits between the brackets of an object/array
+trait Slot {
+    fn start(&self) -> usize;
+    fn value_end(&self) -> usize;
+    fn comma(&self) -> Option<usize>;
+}
+
+impl Slot for Member {
+    fn start(&self) -> usize { self.start }
+    fn value_end(&self) -> usize { self.value.span.end }
+    fn comma(&self) -> Option<usize> { self.comma }
+}
+
+impl Slot for Element {
+    fn start(&self) -> usize { self.value.span.start }
+    fn value_end(&self) -> usize { self.value.span.end }
+    fn comma(&self) -> Option<usize> { self.comma }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    // Skips whitespace and comments
+    fn skip(&mut self) {
+        loop {
+            let rest = &self.text[self.position..];
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => self.position += end + 4,
+                    None => self.position = self.text.len(),
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<Node> {
+        self.skip();
+        let start = self.position;
+        let kind = match self.peek()? {
+            '{' => NodeKind::Object(self.parse_object()?),
+            '[' => NodeKind::Array(self.parse_array()?),
+            '"' | '\'' => {
+                self.parse_string()?;
+                NodeKind::Scalar
+            }
+            _ => {
+                // Numbers, booleans, null, Infinity, NaN..
+                let rest = &self.text[self.position..];
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, ',' | '}' | ']' | '/'))
+                    .unwrap_or(rest.len());
+                if end == 0 {
+                    return None;
+                }
+                self.position += end;
+                NodeKind::Scalar
+            }
+        };
+        Some(Node { kind, span: start..self.position })
+    }
+
+    // Returns the raw string, quotes included
+    fn parse_string(&mut self) -> Option<&str> {
+        let start = self.position;
+        let quote = self.peek()?;
+        let mut chars = self.text[start + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == '\\' {
+                chars.next();
+            } else if c == quote {
+                self.position = start + 1 + i + 1;
+                return Some(&self.text[start..self.position]);
+            }
+        }
+        None
+    }
+
+    // Skips over a trailing comma, if there is one
+    fn parse_comma(&mut self) -> Option<usize> {
+        self.skip();
+        if self.peek() == Some(',') {
+            self.position += 1;
+            Some(self.position - 1)
+        } else {
+            None
+        }
+    }
+
+    fn parse_object(&mut self) -> Option<Vec<Member>> {
+        self.position += 1;
+        let mut members = Vec::new();
+        loop {
+            self.skip();
+            if self.peek()? == '}' {
+                self.position += 1;
+                return Some(members);
+            }
+
+            // Key
+            let start = self.position;
+            let (key, bare) = match self.peek()? {
+                '"' | '\'' => (unquote(self.parse_string()?)?, false),
+                _ => {
+                    let rest = &self.text[self.position..];
+                    let end = rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                        .unwrap_or(rest.len());
+                    if end == 0 {
+                        return None;
+                    }
+                    self.position += end;
+                    (rest[..end].to_string(), true)
+                }
+            };
+            self.skip();
+            if self.peek()? != ':' {
+                return None;
+            }
+            self.position += 1;
+
+            let value = self.parse_value()?;
+            let comma = self.parse_comma();
+            let done = comma.is_none();
+            members.push(Member { key, bare, start, value, comma });
+            if done {
+                self.skip();
+                if self.peek()? != '}' {
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Option<Vec<Element>> {
+        self.position += 1;
+        let mut elements = Vec::new();
+        loop {
+            self.skip();
+            if self.peek()? == ']' {
+                self.position += 1;
+                return Some(elements);
+            }
+
+            let value = self.parse_value()?;
+            let comma = self.parse_comma();
+            let done = comma.is_none();
+            elements.push(Element { value, comma });
+            if done {
+                self.skip();
+                if self.peek()? != ']' {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+// Turns a quoted key into the key itself
+fn unquote(raw: &str) -> Option<String> {
+    #[cfg(feature = "json5")]
+    return json5::from_str(raw).ok();
+
+    // Plain JSON only has double quotes
+    #[cfg(not(feature = "json5"))]
+    return serde_json::from_str(raw).ok();
+}
+
+fn merge_node(text: &str, node: &Node, original: &Value, updated: &Value, edits: &mut Vec<(Range<usize>, String)>) -> Option<()> {
+    if original == updated {
+        return Some(());
+    }
+
+    match (&node.kind, original, updated) {
+        (NodeKind::Object(members), Value::Object(original), Value::Object(updated)) if !members.is_empty() => {
+            merge_object(text, node, members, original, updated, edits)
+        }
+        (NodeKind::Array(elements), Value::Array(original), Value::Array(updated)) if !elements.is_empty() => {
+            merge_array(text, node, elements, original, updated, edits)
+        }
+        _ => {
+            let indent = line_indent(text, node.span.start);
+            edits.push((node.span.clone(), render(updated, indent, is_multiline(text, node))?));
+            Some(())
+        }
+    }
+}
+
+fn merge_object(text: &str, node: &Node, members: &[Member], original: &Map<String, Value>, updated: &Map<String, Value>, edits: &mut Vec<(Range<usize>, String)>) -> Option<()> {
+    let mut kept = Vec::new();
+    for member in members {
+        match updated.get(&member.key) {
+            Some(new_value) => {
+                let old_value = original.get(&member.key).unwrap_or(&Value::Null);
+                merge_node(text, &member.value, old_value, new_value, edits)?;
+                kept.push(member);
+            }
+            None => remove_slot(text, member, edits),
+        }
+    }
+
+    // Adding the new keys at the end of the object
+    let bare_keys = members.iter().all(|member| member.bare);
+    let indent = line_indent(text, members.first()?.start);
+    let multiline = is_multiline(text, node);
+    let mut added = Vec::new();
+    for (key, value) in updated {
+        if !original.contains_key(key) {
+            let key = if bare_keys && is_identifier(key) {
+                key.clone()
+            } else {
+                serde_json::to_string(key).ok()?
+            };
+            added.push(format!("{key}: {}", render(value, indent, multiline)?));
+        }
+    }
+
+    fix_commas(text, node, members, &kept, added, multiline, edits)
+}
+
+fn merge_array(text: &str, node: &Node, elements: &[Element], original: &[Value], updated: &[Value], edits: &mut Vec<(Range<usize>, String)>) -> Option<()> {
+    let mut kept = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        match updated.get(index) {
+            Some(new_value) => {
+                let old_value = original.get(index).unwrap_or(&Value::Null);
+                merge_node(text, &element.value, old_value, new_value, edits)?;
+                kept.push(element);
+            }
+            None => remove_slot(text, element, edits),
+        }
+    }
+
+    // Adding the new items at the end of the array
+    let indent = line_indent(text, elements.first()?.value.span.start);
+    let multiline = is_multiline(text, node);
+    let mut added = Vec::new();
+    for value in updated.iter().skip(elements.len()) {
+        added.push(render(value, indent, multiline)?);
+    }
+
+    fix_commas(text, node, elements, &kept, added, multiline, edits)
+}
+
+// Appends new entries after the last kept one, and makes sure the commas still separate everything.
+// Trailing commas are only kept if the original object/array used them.
+fn fix_commas<S: Slot>(text: &str, node: &Node, slots: &[S], kept: &[&S], added: Vec<String>, multiline: bool, edits: &mut Vec<(Range<usize>, String)>) -> Option<()> {
+    let trailing_comma = slots.last()?.comma().is_some();
+    let indent = line_indent(text, slots.first()?.start());
+
+    let Some(last) = kept.last() else {
+        // Everything got removed, so the new entries go right after the opening bracket
+        if !added.is_empty() {
+            let separator = if multiline { format!("\n{indent}") } else { String::new() };
+            let mut inserted = format!("{separator}{}", added.join(&format!(",{separator}")));
+            if trailing_comma {
+                inserted.push(',');
+            }
+            edits.push((node.span.start + 1..node.span.start + 1, inserted));
+        }
+        return Some(());
+    };
+
+    if added.is_empty() {
+        if last.comma().is_some() && !trailing_comma {
+            let comma = last.comma()?;
+            edits.push((comma..comma + 1, String::new()));
+        }
+        return Some(());
+    }
+
+    let separator = if multiline { format!("\n{indent}") } else { String::from(" ") };
+    let mut inserted = String::new();
+    for value in added {
+        inserted.push_str(&format!("{separator}{value},"));
+    }
+    if !trailing_comma {
+        inserted.pop();
+    }
+    match last.comma() {
+        Some(comma) => edits.push((comma + 1..comma + 1, inserted)),
+        None => edits.push((last.value_end()..last.value_end(), format!(",{inserted}"))),
+    }
+    Some(())
+}
+
+// Removes an entry, along with its comma and the line it sat on (if it's the only thing on it)
+fn remove_slot<S: Slot>(text: &str, slot: &S, edits: &mut Vec<(Range<usize>, String)>) {
+    let mut start = slot.start();
+    let mut end = slot.comma().map_or(slot.value_end(), |comma| comma + 1);
+
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let rest_of_line = text[end..].find('\n').map_or(text.len(), |i| end + i);
+    if text[line_start..start].trim().is_empty() && text[end..rest_of_line].trim().is_empty() {
+        start = line_start;
+        end = (rest_of_line + 1).min(text.len());
+    } else if slot.comma().is_some() {
+        end += text[end..].len() - text[end..].trim_start_matches([' ', '\t']).len();
+    } else {
+        start = text[..start].trim_end_matches([' ', '\t']).len();
+    }
+    edits.push((start..end, String::new()));
+}
+
+// Renders a value in the style of its surroundings
+fn render(value: &Value, indent: &str, multiline: bool) -> Option<String> {
+    let is_collection = matches!(value, Value::Object(map) if !map.is_empty())
+        || matches!(value, Value::Array(items) if !items.is_empty());
+    if !multiline || !is_collection {
+        return serde_json::to_string(value).ok();
+    }
+
+    let text = serde_json::to_string_pretty(value).ok()?;
+    Some(text.replace('\n', &format!("\n{indent}")))
+}
+
+// Gets the whitespace at the start of the line `offset` is on
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn is_multiline(text: &str, node: &Node) -> bool {
+    text[node.span.clone()].contains('\n')
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
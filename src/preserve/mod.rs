@@ -0,0 +1,20 @@
+// - This module holds the format-preserving "patchers".
+//   Instead of regenerating a config file from scratch on every save,
+//   they take the file contents that were originally read and only
+//   replace the values that actually changed.
+//   This keeps comments, key order, and whitespace the user wrote intact.
+// ------------------------------------------------------------------
+// Every patcher takes in the original file contents, as well as the
+// freshly serialized data, and returns `None` if the original
+// contents could not be understood (the caller then falls back to
+// the freshly serialized data).
+
+// Also used to find where values are in plain JSON files
+#[cfg(any(feature = "json", feature = "json5"))]
+pub mod json5_document;
+
+#[cfg(feature = "toml")]
+pub mod toml_document;
+
+#[cfg(feature = "yaml")]
+pub mod yaml_document;
@@ -0,0 +1,235 @@
+use toml_edit::{ArrayOfTables, DocumentMut, ImDocument, Item, TableLike, Value};
+
+/// Patches the values found in `updated` into the `original` TOML document. <br/>
+/// Comments, key order, whitespace and inline-table style of the original document are kept.
+pub fn merge(original: &str, updated: &str) -> Option<String> {
+    let mut document = original.parse::<DocumentMut>().ok()?;
+    let updated = updated.parse::<DocumentMut>().ok()?;
+
+    // New tables get placed at the end of the document, in the order they were added
+    let mut next_position = last_position(document.as_item()) + 1;
+    merge_table(document.as_table_mut(), updated.as_table(), &mut next_position);
+    let merged = document.to_string();
+
+    // Making sure the patched document actually contains the new data
+    let same = match (merged.parse::<toml::Table>(), updated.to_string().parse::<toml::Table>()) {
+        (Ok(merged), Ok(updated)) => merged == updated,
+        _ => false,
+    };
+    same.then_some(merged)
+}
+
+/// Finds the offset of the key (or array item) at `path` in a TOML document.
+pub fn locate(text: &str, path: &[String]) -> Option<usize> {
+    let document = ImDocument::parse(text).ok()?;
+    locate_in_table(document.as_table(), path)
+}
+
+fn locate_in_table(table: &dyn TableLike, path: &[String]) -> Option<usize> {
+    let (first, rest) = path.split_first()?;
+    let (key, item) = table.get_key_value(first)?;
+    match rest.is_empty() {
+        true => key.span().map(|span| span.start),
+        false => locate_in_item(item, rest),
+    }
+}
+
+fn locate_in_item(item: &Item, path: &[String]) -> Option<usize> {
+    match item {
+        Item::Table(table) => locate_in_table(table, path),
+        Item::ArrayOfTables(array) => {
+            let (first, rest) = path.split_first()?;
+            let table = array.get(first.parse().ok()?)?;
+            match rest.is_empty() {
+                true => table.span().map(|span| span.start),
+                false => locate_in_table(table, rest),
+            }
+        }
+        Item::Value(value) => locate_in_value(value, path),
+        Item::None => None,
+    }
+}
+
+fn locate_in_value(value: &Value, path: &[String]) -> Option<usize> {
+    match value {
+        Value::InlineTable(table) => locate_in_table(table, path),
+        Value::Array(array) => {
+            let (first, rest) = path.split_first()?;
+            let value = array.get(first.parse().ok()?)?;
+            match rest.is_empty() {
+                true => value.span().map(|span| span.start),
+                false => locate_in_value(value, rest),
+            }
+        }
+        _ => None,
+    }
+}
+
+// Merges the keys of two tables (standard or inline), removing keys that no longer exist
+fn merge_table(original: &mut dyn TableLike, updated: &dyn TableLike, next_position: &mut usize) {
+    let removed: Vec<String> = original
+        .iter()
+        .filter(|(key, _)| !updated.contains_key(key))
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for key in removed {
+        original.remove(&key);
+    }
+
+    for (key, item) in updated.iter() {
+        match original.get_key_value_mut(key) {
+            // A value that became a table moves to a `[header]` of its own,
+            // so the comment above the key has to go in front of the header
+            Some((mut original_key, existing)) if existing.is_value() && !item.is_value() => {
+                let prefix = original_key.leaf_decor().prefix().cloned();
+                original_key.leaf_decor_mut().clear();
+                merge_item(existing, item, next_position);
+                let decor = match existing {
+                    Item::Table(table) => Some(table.decor_mut()),
+                    Item::ArrayOfTables(array) => array.get_mut(0).map(|table| table.decor_mut()),
+                    _ => None,
+                };
+                if let (Some(decor), Some(prefix)) = (decor, prefix) {
+                    decor.set_prefix(prefix);
+                }
+            }
+            Some((_, existing)) => merge_item(existing, item, next_position),
+            None => {
+                let mut item = item.clone();
+                reposition(&mut item, next_position);
+                original.insert(key, item);
+            }
+        }
+    }
+}
+
+fn merge_item(original: &mut Item, updated: &Item, next_position: &mut usize) {
+    match (original, updated) {
+        // Arrays of tables (`[[name]]`)
+        (Item::ArrayOfTables(original), Item::ArrayOfTables(updated)) => {
+            merge_array_of_tables(original, updated, next_position);
+        }
+
+        // Plain values
+        (Item::Value(original), Item::Value(updated)) => merge_value(original, updated, next_position),
+
+        // Tables, including a standard table that became an inline one (or the opposite)
+        (original, updated) if original.is_table_like() && updated.is_table_like() => {
+            if let (Some(original), Some(updated)) = (original.as_table_like_mut(), updated.as_table_like()) {
+                merge_table(original, updated, next_position);
+            }
+        }
+
+        // The type of the item changed entirely
+        (original, updated) => {
+            let mut updated = updated.clone();
+            reposition(&mut updated, next_position);
+            *original = updated;
+        }
+    }
+}
+
+fn merge_value(original: &mut Value, updated: &Value, next_position: &mut usize) {
+    if values_equal(original, updated) {
+        return;
+    }
+
+    match (&mut *original, updated) {
+        (Value::InlineTable(original), Value::InlineTable(updated)) => {
+            merge_table(original, updated, next_position);
+        }
+        (Value::Array(original), Value::Array(updated)) if original.len() == updated.len() => {
+            for (original, updated) in original.iter_mut().zip(updated.iter()) {
+                merge_value(original, updated, next_position);
+            }
+        }
+        (original, updated) => {
+            // Keeping the whitespace/comments around the old value
+            let decor = original.decor().clone();
+            *original = updated.clone();
+            *original.decor_mut() = decor;
+        }
+    }
+}
+
+fn merge_array_of_tables(original: &mut ArrayOfTables, updated: &ArrayOfTables, next_position: &mut usize) {
+    // Removing tables that no longer exist
+    while original.len() > updated.len() {
+        original.remove(original.len() - 1);
+    }
+
+    for (index, table) in updated.iter().enumerate() {
+        match original.get_mut(index) {
+            Some(existing) => merge_table(existing, table, next_position),
+            None => {
+                let mut table = table.clone();
+                table.set_position(*next_position);
+                *next_position += 1;
+                original.push(table);
+            }
+        }
+    }
+}
+
+// Compares two values while ignoring their formatting (quotes, spacing, comments, etc.)
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => {
+            a.value() == b.value() || (a.value().is_nan() && b.value().is_nan())
+        }
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| values_equal(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| values_equal(a, b)))
+        }
+        _ => false,
+    }
+}
+
+// Gets the position of the last table in the document
+fn last_position(item: &Item) -> usize {
+    match item {
+        Item::Table(table) => table
+            .iter()
+            .map(|(_, item)| last_position(item))
+            .chain(table.position())
+            .max()
+            .unwrap_or(0),
+        Item::ArrayOfTables(array) => array
+            .iter()
+            .flat_map(|table| table.iter().map(|(_, item)| last_position(item)).chain(table.position()))
+            .max()
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// Tables taken from another document carry that document's positions,
+// which would scatter them around the original document
+fn reposition(item: &mut Item, next_position: &mut usize) {
+    match item {
+        Item::Table(table) => {
+            table.set_position(*next_position);
+            *next_position += 1;
+            for (_, item) in table.iter_mut() {
+                reposition(item, next_position);
+            }
+        }
+        Item::ArrayOfTables(array) => {
+            for table in array.iter_mut() {
+                table.set_position(*next_position);
+                *next_position += 1;
+                for (_, item) in table.iter_mut() {
+                    reposition(item, next_position);
+                }
+            }
+        }
+        _ => {}
+    }
+}
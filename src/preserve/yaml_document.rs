@@ -0,0 +1,686 @@
+use serde_yml::{Mapping, Value};
+use std::ops::Range;
+
+/// Patches the values found in `updated` into the `original` YAML document. <br/>
+/// Comments, anchors/aliases, quoting style and key order of untouched nodes are kept.
+///
+/// Only block-style documents are understood, flow collections (`[..]`, `{..}`)
+/// are treated as single values. Returns [`None`] if the original document couldn't be patched.
+pub fn merge(original: &str, updated: &str) -> Option<String> {
+    let original_value: Value = serde_yml::from_str(original).ok()?;
+    let updated_value: Value = serde_yml::from_str(updated).ok()?;
+
+    let parser = Parser::new(original);
+    let root = parser.parse_document()?;
+
+    let mut edits = Edits::default();
+    parser.merge_node(&root, 0, &original_value, &updated_value, &mut edits)?;
+    let merged = edits.apply(original);
+
+    // Aliases, merge keys, and any other construct the parser doesn't fully understand
+    // could make the patched document mean something else, so it gets checked
+    match serde_yml::from_str::<Value>(&merged) {
+        Ok(value) if value == updated_value => Some(merged),
+        _ => None,
+    }
+}
+
+/// Finds the offset of the key (or sequence item) at `path` in a YAML document.
+pub fn locate(text: &str, path: &[String]) -> Option<usize> {
+    let parser = Parser::new(text);
+    let mut node = parser.parse_document()?;
+    let mut offset = None;
+    for segment in path {
+        let (start, next) = match node.kind {
+            NodeKind::Mapping(entries) => {
+                let entry = entries.into_iter().find(|entry| key_matches(&entry.key, segment))?;
+                (entry.key_start, entry.value)
+            }
+            NodeKind::Sequence(items) => {
+                let item = items.into_iter().nth(segment.parse().ok()?)?;
+                let start = item.value.as_ref().map_or(item.line_start, |value| value.span.start);
+                (start, item.value)
+            }
+            NodeKind::Scalar => return None,
+        };
+        offset = Some(start);
+        match next {
+            Some(next) => node = next,
+            None => break,
+        }
+    }
+    offset
+}
+
+fn key_matches(key: &Value, segment: &str) -> bool {
+    match key {
+        Value::String(key) => key == segment,
+        Value::Number(key) => key.to_string() == segment,
+        Value::Bool(key) => key.to_string() == segment,
+        _ => false,
+    }
+}
+
+// A line of the original document
+struct Line {
+    start: usize,
+    end: usize,
+    next: usize,
+    indent: usize,
+    is_content: bool,
+}
+
+enum NodeKind {
+    Mapping(Vec<Entry>),
+    Sequence(Vec<SeqItem>),
+    /// Scalars, aliases, block scalars and flow collections
+    Scalar,
+}
+
+struct Node {
+    kind: NodeKind,
+    span: Range<usize>,
+    /// Indentation of a mapping/sequence's own entries
+    indent: usize,
+}
+
+struct Entry {
+    key: Value,
+    key_start: usize,
+    /// Offset of the start of the line the key is on
+    line_start: usize,
+    /// If the key doesn't start its line (ex: `- key: value`)
+    compact: bool,
+    colon_end: usize,
+    value: Option<Node>,
+    /// Whether the value starts on the same line as the key
+    inline: bool,
+    end: usize,
+}
+
+struct SeqItem {
+    line_start: usize,
+    compact: bool,
+    value: Option<Node>,
+    inline: bool,
+    end: usize,
+}
+
+#[derive(Default)]
+struct Edits(Vec<(Range<usize>, String)>);
+
+impl Edits {
+    fn replace(&mut self, range: Range<usize>, text: String) {
+        self.0.push((range, text));
+    }
+
+    fn apply(mut self, original: &str) -> String {
+        // Applying from the end so the offsets stay valid.
+        // Insertions at the same offset get applied in reverse so they end up in the order they were made,
+        // and after any replacement starting there so they don't get replaced too
+        let mut indexed: Vec<(usize, (Range<usize>, String))> = self.0.drain(..).enumerate().collect();
+        indexed.sort_by(|(a_index, (a, _)), (b_index, (b, _))| {
+            b.start
+                .cmp(&a.start)
+                .then(a.is_empty().cmp(&b.is_empty()))
+                .then(b_index.cmp(a_index))
+        });
+
+        let mut output = original.to_string();
+        for (_, (range, text)) in indexed {
+            output.replace_range(range, &text);
+        }
+        output
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    lines: Vec<Line>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while start < text.len() {
+            let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+            let next = (end + 1).min(text.len());
+            let line = &text[start..end];
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            let trimmed = line.trim();
+            lines.push(Line {
+                start,
+                end: start + line.len(),
+                next,
+                indent,
+                is_content: !trimmed.is_empty() && !trimmed.starts_with('#'),
+            });
+            start = next;
+        }
+        Self { text, lines }
+    }
+
+    fn parse_document(&self) -> Option<Node> {
+        let mut index = 0;
+        while let Some(line) = self.lines.get(index) {
+            let content = &self.text[line.start..line.end];
+            if !line.is_content || content.starts_with('%') || content.trim_end() == "---" {
+                index += 1;
+            } else {
+                break;
+            }
+        }
+        let line = self.lines.get(index)?;
+        let (node, last) = self.parse_block(index, line.start + line.indent)?;
+
+        // Anything else after the root node (another document, for example) isn't supported
+        let rest_is_empty = self.lines[last + 1..].iter().all(|line| {
+            !line.is_content || &self.text[line.start..line.end] == "..."
+        });
+        rest_is_empty.then_some(node)
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        self.lines.partition_point(|line| line.next <= offset).min(self.lines.len() - 1)
+    }
+
+    fn column(&self, offset: usize) -> usize {
+        offset - self.lines[self.line_of(offset)].start
+    }
+
+    // Next line with actual content, after `index`
+    fn next_content(&self, index: usize) -> Option<usize> {
+        (index + 1..self.lines.len()).find(|&i| self.lines[i].is_content)
+    }
+
+    // Parses a node starting at `offset`, returning it along with the index of its last line
+    fn parse_block(&self, index: usize, offset: usize) -> Option<(Node, usize)> {
+        let rest = &self.text[offset..self.lines[index].end];
+        if rest == "-" || rest.starts_with("- ") {
+            self.parse_sequence(index, offset)
+        } else if self.find_key(offset, self.lines[index].end).is_some() {
+            self.parse_mapping(index, offset)
+        } else {
+            let parent_indent = self.column(offset).saturating_sub(1);
+            let (end, last) = self.scan_value(index, offset, parent_indent)?;
+            Some((Node { kind: NodeKind::Scalar, span: offset..end, indent: parent_indent }, last))
+        }
+    }
+
+    fn parse_mapping(&self, mut index: usize, offset: usize) -> Option<(Node, usize)> {
+        let indent = self.column(offset);
+        let mut entries = Vec::new();
+        let mut key_offset = offset;
+        loop {
+            let (entry, last) = self.parse_entry(index, key_offset, indent)?;
+            entries.push(entry);
+            index = last;
+
+            // The next entry has to be at the same indentation
+            match self.next_content(index) {
+                Some(next) if self.lines[next].indent == indent => {
+                    let line = &self.lines[next];
+                    if self.find_key(line.start + indent, line.end).is_none() {
+                        break;
+                    }
+                    index = next;
+                    key_offset = line.start + indent;
+                }
+                Some(next) if self.lines[next].indent > indent => return None,
+                _ => break,
+            }
+        }
+
+        let end = entries.last()?.end;
+        Some((Node { kind: NodeKind::Mapping(entries), span: offset..end, indent }, index))
+    }
+
+    fn parse_entry(&self, index: usize, offset: usize, indent: usize) -> Option<(Entry, usize)> {
+        let line = &self.lines[index];
+        let colon = self.find_key(offset, line.end)?;
+        let key = serde_yml::from_str::<Value>(self.text[offset..colon].trim()).ok()?;
+        let colon_end = colon + 1;
+
+        let (value, inline, last) = self.parse_value(index, colon_end, indent, true)?;
+        let entry = Entry {
+            key,
+            key_start: offset,
+            line_start: line.start,
+            compact: line.start + line.indent != offset,
+            colon_end,
+            value,
+            inline,
+            end: self.lines[last].next,
+        };
+        Some((entry, last))
+    }
+
+    fn parse_sequence(&self, mut index: usize, offset: usize) -> Option<(Node, usize)> {
+        let indent = self.column(offset);
+        let mut items = Vec::new();
+        let mut dash = offset;
+        loop {
+            let line = &self.lines[index];
+            let (value, inline, last) = self.parse_value(index, dash + 1, indent, false)?;
+            items.push(SeqItem {
+                line_start: line.start,
+                compact: line.start + line.indent != dash,
+                value,
+                inline,
+                end: self.lines[last].next,
+            });
+            index = last;
+
+            match self.next_content(index) {
+                Some(next) if self.lines[next].indent == indent => {
+                    let line = &self.lines[next];
+                    let rest = &self.text[line.start + indent..line.end];
+                    if !(rest == "-" || rest.starts_with("- ")) {
+                        break;
+                    }
+                    index = next;
+                    dash = line.start + indent;
+                }
+                Some(next) if self.lines[next].indent > indent => return None,
+                _ => break,
+            }
+        }
+
+        let end = items.last()?.end;
+        Some((Node { kind: NodeKind::Sequence(items), span: offset..end, indent }, index))
+    }
+
+    // Parses whatever comes after a `key:` or a `-`
+    fn parse_value(&self, index: usize, offset: usize, indent: usize, in_mapping: bool) -> Option<(Option<Node>, bool, usize)> {
+        let line = &self.lines[index];
+        let mut start = offset;
+
+        // Skipping the spaces, anchors and tags
+        loop {
+            let rest = &self.text[start..line.end];
+            let trimmed = rest.trim_start_matches(' ');
+            start += rest.len() - trimmed.len();
+            if trimmed.starts_with('&') || trimmed.starts_with('!') {
+                start += trimmed.find(' ').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+
+        let rest = &self.text[start..line.end];
+        if rest.is_empty() || rest.starts_with('#') {
+            // The value is on the next lines (or is empty)
+            if let Some(next) = self.next_content(index) {
+                let next_line = &self.lines[next];
+                let next_rest = &self.text[next_line.start + next_line.indent..next_line.end];
+                let is_sequence = next_rest == "-" || next_rest.starts_with("- ");
+                if next_line.indent > indent || (in_mapping && next_line.indent == indent && is_sequence) {
+                    let (node, last) = self.parse_block(next, next_line.start + next_line.indent)?;
+                    return Some((Some(node), false, last));
+                }
+            }
+            return Some((None, true, index));
+        }
+
+        // Compact nested nodes (`- - value` or `- key: value`)
+        if !in_mapping && (rest == "-" || rest.starts_with("- ") || self.find_key(start, line.end).is_some()) {
+            let (node, last) = self.parse_block(index, start)?;
+            return Some((Some(node), true, last));
+        }
+
+        let (end, last) = self.scan_value(index, start, indent)?;
+        Some((Some(Node { kind: NodeKind::Scalar, span: start..end, indent }), true, last))
+    }
+
+    // Finds the end of a scalar/flow value, which could go on for multiple lines
+    fn scan_value(&self, index: usize, start: usize, indent: usize) -> Option<(usize, usize)> {
+        let first = self.text[start..].chars().next()?;
+        match first {
+            // Block scalars
+            '|' | '>' => {
+                let mut last = index;
+                let mut end = self.lines[index].end;
+                for i in index + 1..self.lines.len() {
+                    let line = &self.lines[i];
+                    if line.start == line.end || self.text[line.start..line.end].trim().is_empty() {
+                        continue;
+                    }
+                    if line.indent <= indent {
+                        break;
+                    }
+                    last = i;
+                    end = line.end;
+                }
+                Some((end, last))
+            }
+
+            // Quoted scalars and flow collections
+            '"' | '\'' | '[' | '{' => {
+                let end = self.scan_delimited(start)?;
+                Some((end, self.line_of(end - 1)))
+            }
+
+            // Plain scalars, which can continue on more indented lines
+            _ => {
+                let line = &self.lines[index];
+                let mut end = strip_comment(&self.text[start..line.end]).len() + start;
+                let mut last = index;
+                while let Some(next) = self.next_content(last) {
+                    let line = &self.lines[next];
+                    if line.indent <= indent {
+                        break;
+                    }
+                    last = next;
+                    end = line.start + strip_comment(&self.text[line.start..line.end]).len();
+                }
+                Some((end, last))
+            }
+        }
+    }
+
+    // Scans over quotes and brackets, returning the offset right after the closing character
+    fn scan_delimited(&self, start: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut quote: Option<char> = None;
+        let mut chars = self.text[start..].char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match quote {
+                Some('"') => match c {
+                    '\\' => { chars.next(); }
+                    '"' => quote = None,
+                    _ => {}
+                },
+                Some(_) => {
+                    if c == '\'' {
+                        if chars.peek().map(|(_, c)| *c) == Some('\'') {
+                            chars.next();
+                        } else {
+                            quote = None;
+                        }
+                    }
+                }
+                None => match c {
+                    '"' | '\'' => quote = Some(c),
+                    '[' | '{' => depth += 1,
+                    ']' | '}' => depth = depth.checked_sub(1)?,
+                    '#' if self.text[..start + i].ends_with([' ', '\t', '\n']) => {
+                        // Comments inside multi-line flow collections
+                        while chars.peek().is_some_and(|(_, c)| *c != '\n') {
+                            chars.next();
+                        }
+                    }
+                    _ => {}
+                },
+            }
+            if quote.is_none() && depth == 0 {
+                return Some(start + i + c.len_utf8());
+            }
+        }
+        None
+    }
+
+    // Finds the colon of a `key: value` pair, if there is one in this range
+    fn find_key(&self, start: usize, end: usize) -> Option<usize> {
+        let text = &self.text[start..end];
+        let first = text.chars().next()?;
+        let key_end = match first {
+            '"' | '\'' => self.scan_delimited(start)? - start,
+            '[' | '{' | '#' | '&' | '*' | '!' | '|' | '>' | '%' | '@' | '`' | '?' => return None,
+            '-' if text == "-" || text.starts_with("- ") => return None,
+            _ => 0,
+        };
+
+        let mut previous = ' ';
+        for (i, c) in text[key_end..].char_indices() {
+            let i = key_end + i;
+            if c == '#' && previous == ' ' {
+                return None;
+            }
+            if c == ':' && (i + 1 == text.len() || text[i + 1..].starts_with(' ')) {
+                return Some(start + i);
+            }
+            if key_end != 0 && c != ' ' {
+                // Quoted keys have to be directly followed by the colon
+                return None;
+            }
+            previous = c;
+        }
+        None
+    }
+
+    // ---- Merging ----
+
+    fn merge_node(&self, node: &Node, indent: usize, original: &Value, updated: &Value, edits: &mut Edits) -> Option<()> {
+        if original == updated {
+            return Some(());
+        }
+
+        match (&node.kind, original, updated) {
+            (NodeKind::Mapping(entries), Value::Mapping(original), Value::Mapping(updated)) => {
+                self.merge_mapping(node, entries, original, updated, edits)
+            }
+            (NodeKind::Sequence(items), Value::Sequence(original), Value::Sequence(updated)) => {
+                self.merge_sequence(node, items, original, updated, edits)
+            }
+            (NodeKind::Scalar, _, _) => {
+                edits.replace(node.span.clone(), self.render_inline(updated, indent, self.is_flow(node))?);
+                Some(())
+            }
+            // The root node changed its type
+            _ => None,
+        }
+    }
+
+    fn merge_mapping(&self, node: &Node, entries: &[Entry], original: &Mapping, updated: &Mapping, edits: &mut Edits) -> Option<()> {
+        for entry in entries {
+            // Merge keys (`<<: *anchor`) are left alone
+            if entry.key.as_str() == Some("<<") {
+                continue;
+            }
+
+            match updated.get(&entry.key) {
+                None => {
+                    // The first key of a compact mapping shares its line with the `-`
+                    if entry.compact {
+                        return None;
+                    }
+                    edits.replace(entry.line_start..entry.end, String::new());
+                }
+                Some(new_value) => {
+                    let old_value = original.get(&entry.key).unwrap_or(&Value::Null);
+                    self.merge_entry(entry, node.indent, old_value, new_value, edits)?;
+                }
+            }
+        }
+
+        // Adding the new keys at the end of the mapping
+        let mut added = Mapping::new();
+        for (key, value) in updated {
+            if !original.contains_key(key) {
+                added.insert(key.clone(), value.clone());
+            }
+        }
+        if !added.is_empty() {
+            let text = indent_block(&serde_yml::to_string(&added).ok()?, node.indent);
+            self.insert_lines(entries.last()?.end, text, edits);
+        }
+        Some(())
+    }
+
+    fn merge_entry(&self, entry: &Entry, indent: usize, original: &Value, updated: &Value, edits: &mut Edits) -> Option<()> {
+        if original == updated {
+            return Some(());
+        }
+
+        match &entry.value {
+            // Collections with the same type get merged
+            Some(node) if same_collection(node, original, updated) => {
+                self.merge_node(node, indent, original, updated, edits)
+            }
+
+            // Single-line values and flow collections get replaced on the spot
+            Some(node) if entry.inline && self.fits_inline(node, updated) => {
+                edits.replace(node.span.clone(), self.render_inline(updated, indent, self.is_flow(node))?);
+                Some(())
+            }
+
+            // Empty values (`key:`)
+            None if is_inline(updated) => {
+                edits.replace(entry.colon_end..entry.colon_end, format!(" {}", self.render_inline(updated, indent, false)?));
+                Some(())
+            }
+
+            // Anything else has its value rewritten from scratch
+            _ => {
+                let text = if is_inline(updated) {
+                    format!(" {}\n", self.render_inline(updated, indent, false)?)
+                } else {
+                    format!("\n{}", indent_block(&serde_yml::to_string(updated).ok()?, indent + 2))
+                };
+                edits.replace(entry.colon_end..entry.end, text);
+                Some(())
+            }
+        }
+    }
+
+    fn merge_sequence(&self, node: &Node, items: &[SeqItem], original: &[Value], updated: &[Value], edits: &mut Edits) -> Option<()> {
+        for (index, item) in items.iter().enumerate() {
+            let Some(new_value) = updated.get(index) else {
+                // Removing items that no longer exist
+                if item.compact {
+                    return None;
+                }
+                edits.replace(item.line_start..item.end, String::new());
+                continue;
+            };
+            let old_value = original.get(index).unwrap_or(&Value::Null);
+            if old_value == new_value {
+                continue;
+            }
+
+            match &item.value {
+                Some(value) if same_collection(value, old_value, new_value) => {
+                    self.merge_node(value, value.indent, old_value, new_value, edits)?;
+                }
+                Some(value) if item.inline && self.fits_inline(value, new_value) => {
+                    edits.replace(value.span.clone(), self.render_inline(new_value, node.indent, self.is_flow(value))?);
+                }
+                _ => {
+                    if item.compact {
+                        return None;
+                    }
+                    let text = serde_yml::to_string(&vec![new_value.clone()]).ok()?;
+                    edits.replace(item.line_start..item.end, indent_block(&text, node.indent));
+                }
+            }
+        }
+
+        // Adding the new items at the end of the sequence
+        if updated.len() > items.len() {
+            let text = serde_yml::to_string(&updated[items.len()..].to_vec()).ok()?;
+            self.insert_lines(items.last()?.end, indent_block(&text, node.indent), edits);
+        }
+        Some(())
+    }
+
+    // Inserts whole lines at an offset that should be at the start of a line
+    fn insert_lines(&self, offset: usize, text: String, edits: &mut Edits) {
+        if offset == self.text.len() && !self.text.is_empty() && !self.text.ends_with('\n') {
+            edits.replace(offset..offset, format!("\n{text}"));
+        } else {
+            edits.replace(offset..offset, text);
+        }
+    }
+
+    // Whether a scalar node can be replaced by the new value without changing the lines around it
+    fn fits_inline(&self, node: &Node, value: &Value) -> bool {
+        matches!(node.kind, NodeKind::Scalar)
+            && (is_inline(value) || self.render_inline(value, 0, self.is_flow(node)).is_some())
+    }
+
+    fn is_flow(&self, node: &Node) -> bool {
+        self.text[node.span.clone()].starts_with(['[', '{'])
+    }
+
+    // Renders a value so it fits on the line it's placed in
+    fn render_inline(&self, value: &Value, indent: usize, flow: bool) -> Option<String> {
+        match value {
+            Value::Sequence(items) if flow || items.is_empty() => {
+                let items: Option<Vec<String>> = items.iter().map(|item| self.render_inline(item, indent, true)).collect();
+                Some(format!("[{}]", items?.join(", ")))
+            }
+            Value::Mapping(mapping) if flow || mapping.is_empty() => {
+                let mut entries = Vec::new();
+                for (key, value) in mapping {
+                    entries.push(format!("{}: {}", self.render_inline(key, indent, true)?, self.render_inline(value, indent, true)?));
+                }
+                Some(format!("{{{}}}", entries.join(", ")))
+            }
+            Value::Sequence(_) | Value::Mapping(_) => None,
+            _ => {
+                let text = serde_yml::to_string(value).ok()?;
+                let text = text.trim_end_matches('\n');
+                if flow && text.contains('\n') {
+                    return None;
+                }
+                // Multi-line strings (block scalars) need to be indented past their key
+                let mut lines = text.split('\n');
+                let mut output = lines.next()?.to_string();
+                for line in lines {
+                    output.push('\n');
+                    if !line.is_empty() {
+                        output.push_str(&" ".repeat(indent));
+                    }
+                    output.push_str(line);
+                }
+                Some(output)
+            }
+        }
+    }
+}
+
+// Whether the node can be merged with the new value instead of being replaced
+fn same_collection(node: &Node, original: &Value, updated: &Value) -> bool {
+    match node.kind {
+        NodeKind::Mapping(_) => matches!((original, updated), (Value::Mapping(_), Value::Mapping(_))),
+        NodeKind::Sequence(_) => matches!((original, updated), (Value::Sequence(_), Value::Sequence(_))),
+        NodeKind::Scalar => false,
+    }
+}
+
+// Whether the value can be written on the same line as its key
+fn is_inline(value: &Value) -> bool {
+    match value {
+        Value::Sequence(items) => items.is_empty(),
+        Value::Mapping(mapping) => mapping.is_empty(),
+        Value::Tagged(tagged) => is_inline(&tagged.value),
+        _ => true,
+    }
+}
+
+fn indent_block(text: &str, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+    let mut output = String::new();
+    for line in text.lines() {
+        if !line.is_empty() {
+            output.push_str(&padding);
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+// Removes a trailing `# comment` and whitespace from a line
+fn strip_comment(line: &str) -> &str {
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '#' && (previous == ' ' || previous == '\t') {
+            return line[..i].trim_end();
+        }
+        previous = c;
+    }
+    line.trim_end()
+}
@@ -0,0 +1,148 @@
+use crate::env::Override;
+use crate::value::{self, Value};
+use crate::ConfigFormat;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+// - This module keeps track of where every value of a config came from.
+//   Every "leaf" of the data (anything that isn't a table) gets a `Source`
+//   when the config is loaded; values changed by the program afterwards
+//   are found by comparing the data against what was loaded.
+
+/// Where a value of a [`Config`](crate::Config) came from. <br/>
+/// See [`Config::source_of`](crate::Config::source_of) and [`Config::sources`](crate::Config::sources).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// The data the config was constructed with
+    Default,
+
+    /// A config file *(or layer)*
+    /// - `path` is empty for configs made with [`Config::from_str`](crate::Config::from_str)
+    /// - `line` and `column` start at 1, and are [`None`] if the format can't tell where the value is
+    File {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+
+    /// An environment variable override, stores the name of the variable
+    Env(String),
+
+    /// Changed by the program after the config was loaded
+    Programmatic,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default value"),
+            Source::File { path, line, column } => {
+                write!(f, "{}", path.display())?;
+                if let Some(line) = line {
+                    write!(f, ":{line}")?;
+                }
+                if let Some(column) = column {
+                    write!(f, ":{column}")?;
+                }
+                Ok(())
+            }
+            Source::Env(var) => write!(f, "environment variable {var}"),
+            Source::Programmatic => write!(f, "set by the program"),
+        }
+    }
+}
+
+// A file the data was read from, parsed into a `Value` if it could be
+pub(crate) struct SourceFile<'a> {
+    pub path: &'a Path,
+    pub format: ConfigFormat,
+    pub content: &'a str,
+    pub value: Option<Value>,
+}
+
+// The sources of the data as it was loaded
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Provenance {
+    /// The data right after loading
+    pub loaded: Value,
+    /// The source of every leaf of `loaded`
+    pub sources: Vec<(Vec<String>, Source)>,
+}
+
+impl Provenance {
+    pub fn new(loaded: Value, files: &[SourceFile], overrides: &[Override]) -> Self {
+        let mut sources: Vec<(Vec<String>, Source)> = value::leaves(&loaded)
+            .into_iter()
+            .map(|path| (path, Source::Default))
+            .collect();
+
+        // Later sources win
+        for file in files {
+            let backend = crate::backend::get_backend(file.format);
+            let located = |path: &[String]| {
+                let offset = backend.as_ref().and_then(|backend| backend.locate(file.content, path));
+                let (line, column) = offset.map(|offset| crate::utils::line_column(file.content, offset)).unzip();
+                Source::File { path: file.path.to_path_buf(), line, column }
+            };
+            match &file.value {
+                Some(value) => {
+                    for path in value::leaves(value) {
+                        if let Some((_, source)) = sources.iter_mut().find(|(existing, _)| *existing == path) {
+                            *source = located(&path);
+                        }
+                    }
+                }
+                // No way to tell which values the file has, so assuming it has all of them
+                None => {
+                    for (path, source) in sources.iter_mut() {
+                        *source = located(path);
+                    }
+                }
+            }
+        }
+        for applied in overrides {
+            if let Some((_, source)) = sources.iter_mut().find(|(path, _)| *path == applied.path) {
+                *source = Source::Env(applied.var.clone());
+            }
+        }
+
+        Self { loaded, sources }
+    }
+
+    // Gets the source of the value at `path`, given what the data currently is
+    pub fn source_of(&self, current: &Value, path: &[String]) -> Option<Source> {
+        let value = value::get_at(current, path)?;
+
+        // Tables only have a source if everything in them comes from the same place
+        if let Value::Table(table) = value {
+            if !table.is_empty() {
+                let mut sources = table.keys().map(|key| {
+                    let mut inner = path.to_vec();
+                    inner.push(key.clone());
+                    self.source_of(current, &inner)
+                });
+                let first = sources.next()??;
+                return sources.all(|source| source.as_ref() == Some(&first)).then_some(first);
+            }
+        }
+
+        if value::get_at(&self.loaded, path) != Some(value) {
+            return Some(Source::Programmatic);
+        }
+
+        // The value itself, or the array it's in
+        let source = self.sources.iter().find(|(leaf, _)| path.starts_with(leaf));
+        Some(source.map_or(Source::Default, |(_, source)| source.clone()))
+    }
+
+    // Gets the source of every value, given what the data currently is
+    pub fn all(&self, current: &Value) -> Vec<(String, Source)> {
+        value::leaves(current)
+            .into_iter()
+            .filter_map(|path| {
+                let source = self.source_of(current, &path)?;
+                Some((path.join("."), source))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::ConfigError;
+use crate::value::{Table, Value};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// - This module gets configs out of config files that fail to deserialize,
+//   either by starting over from the default data, or by keeping what can be kept of the file.
+// ------------------------------------------------------------------
+// Salvaging merges the file into the defaults one field at a time,
+// and drops every field that makes the data fail to deserialize.
+
+/// What to do when the config file can't be deserialized, inside of [`ConfigSetupOptions`](crate::ConfigSetupOptions). <br/>
+/// See [`Config::recovered_from`](crate::Config::recovered_from) to know if a config was recovered.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum Recovery {
+    /// Constructing the config fails with the deserialization error.
+    #[default]
+    Fail,
+
+    /// The file is moved to `<file>.corrupt-<timestamp>`, and the config starts over from the default data.
+    Reset,
+
+    /// The file is moved to `<file>.corrupt-<timestamp>`, and every field of it that still deserializes is kept.
+    /// The other fields get their default value. <br/>
+    /// Files with syntax errors can't be read at all, so they are reset instead.
+    Salvage,
+}
+
+/// How a config file that couldn't be deserialized was recovered from, see [`Config::recovered_from`](crate::Config::recovered_from)
+///
+/// # Attributes
+/// - `moved_to` - Where the broken file was moved to *(ex: `config.toml.corrupt-20240131-235959-123`)*
+/// - `error` - Why the file couldn't be deserialized
+/// - `reset_keys` - The dotted paths of the fields of the file that got dropped for their default value
+///   *(only with [`Recovery::Salvage`], empty if the whole file was dropped)*
+#[derive(Debug)]
+pub struct Recovered {
+    pub moved_to: PathBuf,
+    pub error: ConfigError,
+    pub reset_keys: Vec<String>,
+}
+
+// Renames a broken config file to `<file>.corrupt-<timestamp>`, returning its new path
+pub(crate) fn move_aside(path: &Path) -> io::Result<PathBuf> {
+    let moved_to = crate::backup::suffixed_path(path, &format!("corrupt-{}", crate::utils::timestamp()));
+    fs::rename(path, &moved_to)?;
+    Ok(moved_to)
+}
+
+// Merges `top` into `base` one field at a time, keeping only the fields `D` still deserializes with.
+// Returns the merged value, and the dotted paths of the fields that were dropped
+pub(crate) fn salvage<D: DeserializeOwned>(base: Value, top: Value) -> (Value, Vec<String>) {
+    let mut merged = base;
+    let mut dropped = Vec::new();
+    // Nothing can be kept if even the base doesn't fit the data
+    if crate::value::from_value::<D>(merged.clone()).is_ok() {
+        salvage_table::<D>(&mut merged, &mut Vec::new(), top, &mut dropped);
+    }
+    (merged, dropped)
+}
+
+fn salvage_table<D: DeserializeOwned>(merged: &mut Value, path: &mut Vec<String>, top: Value, dropped: &mut Vec<String>) {
+    let Value::Table(top) = top else {
+        return;
+    };
+    for (key, value) in top {
+        path.push(key);
+        let mut candidate = merged.clone();
+        crate::layers::merge(&mut candidate, nest(path, value.clone()));
+        match value {
+            _ if crate::value::from_value::<D>(candidate.clone()).is_ok() => *merged = candidate,
+            // Some of the table might still be fine
+            Value::Table(table) if !table.is_empty() => salvage_table::<D>(merged, path, Value::Table(table), dropped),
+            _ => dropped.push(path.join(".")),
+        }
+        path.pop();
+    }
+}
+
+// Gets the dotted paths of the fields `merged` only has because of `base`, which `D` can't do without.
+// Fields under one of the `skipped` paths are left out
+pub(crate) fn missing<D: DeserializeOwned>(merged: &Value, base: &Value, top: &Value, skipped: &[String]) -> Vec<String> {
+    crate::value::leaves(base)
+        .into_iter()
+        .filter(|path| crate::value::get_at(top, path).is_none())
+        .map(|path| (path.join("."), path))
+        .filter(|(key, _)| !skipped.iter().any(|skipped| key == skipped || key.starts_with(&format!("{skipped}."))))
+        .filter(|(_, path)| {
+            let mut without = merged.clone();
+            crate::value::remove_at(&mut without, path);
+            crate::value::from_value::<D>(without).is_err()
+        })
+        .map(|(key, _)| key)
+        .collect()
+}
+
+// Puts `value` inside of tables, so that it ends up at `path`
+fn nest(path: &[String], value: Value) -> Value {
+    path.iter().rev().fold(value, |nested, key| {
+        let mut table = Table::new();
+        table.insert(key.clone(), nested);
+        Value::Table(table)
+    })
+}
@@ -0,0 +1,242 @@
+use crate::value::{Table, Value};
+
+// - This module reads RON into a `Value` without losing the names of enum variants.
+//   It's used whenever the data goes through a `Value` (migrations, layers, unknown keys..).
+// ------------------------------------------------------------------
+// RON writes enums by their variant names, which the `ron` crate drops when reading into a `Value`.
+// Here they get turned into what `value::to_value` makes of them instead
+// (`Dark` -> `"Dark"`, `Custom("x")` -> `{ Custom: "x" }`). Named structs (`Name(key: value)`) can't be
+// told apart from enum variants, so they read the same way, and only get unwrapped when deserialized.
+// Writing RON through a `Value` is handled by the `shape` module.
+
+// Reads RON text into a `Value`, keeping the names of enum variants. <br/>
+// Returns `None` for anything it doesn't understand, which the `ron` crate should be asked about instead
+pub(crate) fn read(text: &str) -> Option<Value> {
+    let mut reader = Reader { text, position: 0 };
+    reader.skip();
+    // Extensions (`#![enable(..)]`) change how RON reads some types, which only the `ron` crate knows about
+    if reader.rest().starts_with("#!") {
+        return None;
+    }
+    let value = reader.value()?;
+    reader.skip();
+    reader.rest().is_empty().then_some(value)
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    // Skips over `expected` if it's next
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip();
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += expected.len_utf8();
+        }
+        found
+    }
+
+    // Skips whitespace and comments
+    fn skip(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                // Block comments can be nested
+                let mut depth = 0;
+                let mut index = 0;
+                while index < trimmed.len() {
+                    if trimmed[index..].starts_with("/*") {
+                        depth += 1;
+                        index += 2;
+                    } else if trimmed[index..].starts_with("*/") {
+                        depth -= 1;
+                        index += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        index += trimmed[index..].chars().next().map_or(1, char::len_utf8);
+                    }
+                }
+                self.position += index;
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Whether `expected` is next, without skipping over it
+    fn next_is(&mut self, expected: char) -> bool {
+        self.skip();
+        self.peek() == Some(expected)
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        let first = rest.chars().next()?;
+        if end == 0 || first.is_ascii_digit() {
+            return None;
+        }
+        self.position += end;
+        Some(&self.text[self.position - end..self.position])
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip();
+        match self.peek()? {
+            '(' => self.parenthesized(),
+            '[' => {
+                self.position += 1;
+                let mut values = Vec::new();
+                while !self.eat(']') {
+                    values.push(self.value()?);
+                    if !self.eat(',') && !self.next_is(']') {
+                        return None;
+                    }
+                }
+                Some(Value::Array(values))
+            }
+            '{' => {
+                self.position += 1;
+                let mut table = Table::new();
+                while !self.eat('}') {
+                    let key = match self.value()? {
+                        Value::String(key) => key,
+                        Value::Integer(key) => key.to_string(),
+                        Value::Bool(key) => key.to_string(),
+                        _ => return None,
+                    };
+                    if !self.eat(':') {
+                        return None;
+                    }
+                    table.insert(key, self.value()?);
+                    if !self.eat(',') && !self.next_is('}') {
+                        return None;
+                    }
+                }
+                Some(Value::Table(table))
+            }
+            // Raw strings and byte strings start like identifiers
+            'r' | 'b' if matches!(self.rest()[1..].chars().next(), Some('"' | '#')) => self.scalar(),
+            c if c.is_alphabetic() || c == '_' => {
+                let name = self.identifier()?;
+                match name {
+                    "true" => return Some(Value::Bool(true)),
+                    "false" => return Some(Value::Bool(false)),
+                    "None" => return Some(Value::Null),
+                    "inf" => return Some(Value::Float(f64::INFINITY)),
+                    "NaN" => return Some(Value::Float(f64::NAN)),
+                    _ => {}
+                }
+                self.skip();
+                if self.peek() != Some('(') {
+                    // A unit variant
+                    return Some(Value::String(name.to_string()));
+                }
+                let inner = self.parenthesized()?;
+                if name == "Some" {
+                    return match inner {
+                        Value::Array(mut values) if values.len() == 1 => values.pop(),
+                        _ => None,
+                    };
+                }
+                // A variant holding data, with a single value written without the tuple around it
+                let inner = match inner {
+                    Value::Array(mut values) if values.len() == 1 => values.pop()?,
+                    inner => inner,
+                };
+                let mut table = Table::new();
+                table.insert(name, inner);
+                Some(Value::Table(table))
+            }
+            _ => self.scalar(),
+        }
+    }
+
+    // `()`, `(key: value, ..)` or `(value, ..)`
+    fn parenthesized(&mut self) -> Option<Value> {
+        self.position += 1;
+        if self.eat(')') {
+            return Some(Value::Null);
+        }
+
+        // Structs are told apart from tuples by their first key
+        let start = self.position;
+        self.skip();
+        let is_struct = self.identifier().is_some() && self.eat(':');
+        self.position = start;
+
+        let mut table = Table::new();
+        let mut values = Vec::new();
+        while !self.eat(')') {
+            if is_struct {
+                self.skip();
+                let key = self.identifier()?;
+                if !self.eat(':') {
+                    return None;
+                }
+                table.insert(key, self.value()?);
+            } else {
+                values.push(self.value()?);
+            }
+            if !self.eat(',') && !self.next_is(')') {
+                return None;
+            }
+        }
+        match is_struct {
+            true => Some(Value::Table(table)),
+            false => Some(Value::Array(values)),
+        }
+    }
+
+    // Numbers, strings and characters, which the `ron` crate reads
+    fn scalar(&mut self) -> Option<Value> {
+        let rest = self.rest();
+        let end = match rest.chars().next()? {
+            '"' | '\'' => Self::quoted_length(rest)?,
+            'r' => {
+                let hashes = rest[1..].chars().take_while(|&c| c == '#').count();
+                let closing = format!("\"{}", "#".repeat(hashes));
+                let body = 1 + hashes + 1;
+                body + rest.get(body..)?.find(&closing)? + closing.len()
+            }
+            'b' => 1 + Self::quoted_length(&rest[1..])?,
+            _ => rest
+                .find(|c: char| c.is_whitespace() || matches!(c, ',' | ')' | ']' | '}' | ':' | '/'))
+                .unwrap_or(rest.len()),
+        };
+        let value = ron::from_str(&rest[..end]).ok()?;
+        self.position += end;
+        Some(value)
+    }
+
+    // The length of a quoted string or character, quotes included
+    fn quoted_length(text: &str) -> Option<usize> {
+        let quote = text.chars().next()?;
+        let mut chars = text.char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            if c == '\\' {
+                chars.next();
+            } else if c == quote {
+                return Some(index + 1);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,49 @@
+use crate::value::{Table, Value};
+use std::path::{Path, PathBuf};
+
+// - This module links config files to a JSON Schema of their data,
+//   so that editors can autocomplete and check them.
+//   JSON, JSON5 and YAML files get a `$schema` key, TOML files get a `#:schema` comment.
+// ------------------------------------------------------------------
+// Generating the schema itself needs the `schema` feature (through `schemars`),
+// referencing an existing schema doesn't.
+
+/// The key JSON, JSON5 and YAML files reference their schema with
+pub(crate) const KEY: &str = "$schema";
+
+// Generates the JSON Schema of `D`
+#[cfg(feature = "schema")]
+pub(crate) fn generate<D: schemars::JsonSchema>() -> String {
+    let schema = schemars::schema_for!(D);
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+// Where the schema of a config file goes (ex: `config.toml` -> `config.schema.json`)
+pub(crate) fn path_for(config_path: &Path) -> PathBuf {
+    let stem = config_path.file_stem().unwrap_or_default().to_string_lossy();
+    config_path.with_file_name(format!("{stem}.schema.json"))
+}
+
+// Puts the `$schema` key in front of the other keys of `value`
+pub(crate) fn embed_key(value: Value, reference: &str) -> Value {
+    let Value::Table(table) = value else {
+        return value;
+    };
+    let mut embedded = Table::new();
+    embedded.insert(KEY, Value::String(reference.to_string()));
+    for (key, value) in table {
+        if key != KEY {
+            embedded.insert(key, value);
+        }
+    }
+    Value::Table(embedded)
+}
+
+// Puts a `#:schema` comment on the first line of a TOML file, replacing the one that's already there
+pub(crate) fn embed_comment(text: &str, reference: &str) -> String {
+    let rest = match text.strip_prefix("#:schema") {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, rest)| rest),
+        None => text,
+    };
+    format!("#:schema {reference}\n{rest}")
+}
@@ -0,0 +1,207 @@
+use crate::value::{self, Table, Value};
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+
+// - This module writes the data in the shape of a `Value` that was made from it,
+//   for formats where going through `Value` would lose how the data is written.
+// ------------------------------------------------------------------
+// A `Value` only knows tables and strings, so saving one directly turns RON structs and enums
+// into `{"key": value}` maps and strings, and TOML datetimes into `{"$__toml_private_datetime": ..}` tables.
+// Instead, the data itself gets serialized, skipping the keys the `Value` left out
+// (keys the same as the layers below) and falling back to the `Value` wherever the two differ
+// (environment variable overrides, which get written as what they replaced).
+
+// The data, written in the shape of `shape`. <br/>
+// `stamp` is a top-level key only `shape` has (the migrations' version), which gets written first
+pub(crate) struct Shaped<'a, D: ?Sized> {
+    pub data: &'a D,
+    pub shape: &'a Value,
+    pub stamp: Option<&'static str>,
+}
+
+impl<D: Serialize + ?Sized> Serialize for Shaped<'_, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.shape {
+            Value::Table(table) => self.data.serialize(ShapeSerializer { inner: serializer, shape: self.shape, table, stamp: self.stamp }),
+            _ => self.data.serialize(serializer),
+        }
+    }
+}
+
+// One value inside of the data, written as-is if it matches its part of the shape
+enum Part<'a, T: ?Sized> {
+    Data(&'a T),
+    Shaped(Shaped<'a, T>),
+    Value(&'a Value),
+}
+
+impl<'a, T: Serialize + ?Sized> Part<'a, T> {
+    fn new(data: &'a T, shape: &'a Value) -> Self {
+        if value::to_value(data).is_ok_and(|value| &value == shape) {
+            return Part::Data(data);
+        }
+        match shape {
+            Value::Table(_) => Part::Shaped(Shaped { data, shape, stamp: None }),
+            _ => Part::Value(shape),
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Part<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Part::Data(data) => data.serialize(serializer),
+            Part::Shaped(shaped) => shaped.serialize(serializer),
+            Part::Value(value) => value.serialize(serializer),
+        }
+    }
+}
+
+// Passes everything through to the format's serializer, except for structs and maps,
+// which only get the keys `shape` has
+struct ShapeSerializer<'a, S> {
+    inner: S,
+    shape: &'a Value,
+    table: &'a Table,
+    stamp: Option<&'static str>,
+}
+
+macro_rules! pass_through {
+    ($($method:ident($($arg:ident: $type:ty),*) -> $output:ty;)*) => {
+        $(fn $method(self, $($arg: $type),*) -> Result<$output, S::Error> {
+            self.inner.$method($($arg),*)
+        })*
+    };
+}
+
+impl<'a, S: Serializer> Serializer for ShapeSerializer<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = ShapeMap<'a, S::SerializeMap>;
+    type SerializeStruct = ShapeStruct<'a, S::SerializeStruct>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    pass_through!(
+        serialize_bool(value: bool) -> S::Ok;
+        serialize_i8(value: i8) -> S::Ok;
+        serialize_i16(value: i16) -> S::Ok;
+        serialize_i32(value: i32) -> S::Ok;
+        serialize_i64(value: i64) -> S::Ok;
+        serialize_i128(value: i128) -> S::Ok;
+        serialize_u8(value: u8) -> S::Ok;
+        serialize_u16(value: u16) -> S::Ok;
+        serialize_u32(value: u32) -> S::Ok;
+        serialize_u64(value: u64) -> S::Ok;
+        serialize_u128(value: u128) -> S::Ok;
+        serialize_f32(value: f32) -> S::Ok;
+        serialize_f64(value: f64) -> S::Ok;
+        serialize_char(value: char) -> S::Ok;
+        serialize_str(value: &str) -> S::Ok;
+        serialize_bytes(value: &[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(name: &'static str) -> S::Ok;
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> S::Ok;
+        serialize_seq(len: Option<usize>) -> S::SerializeSeq;
+        serialize_tuple(len: usize) -> S::SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> S::SerializeTupleStruct;
+        serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeTupleVariant;
+        serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeStructVariant;
+    );
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&Shaped { data: value, shape: self.shape, stamp: self.stamp })
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(name, &Shaped { data: value, shape: self.shape, stamp: self.stamp })
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, name: &'static str, index: u32, variant: &'static str, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let mut inner = self.inner.serialize_map(Some(self.table.len()))?;
+        if let Some(key) = self.stamp {
+            if let Some(stamp) = self.table.get(key) {
+                inner.serialize_entry(key, stamp)?;
+            }
+        }
+        Ok(ShapeMap { inner, shape: self.table, part: None })
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct, S::Error> {
+        let mut inner = self.inner.serialize_struct(name, self.table.len())?;
+        if let Some(key) = self.stamp {
+            if let Some(stamp) = self.table.get(key) {
+                inner.serialize_field(key, stamp)?;
+            }
+        }
+        Ok(ShapeStruct { inner, shape: self.table, stamp: self.stamp })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct ShapeStruct<'a, S> {
+    inner: S,
+    shape: &'a Table,
+    stamp: Option<&'static str>,
+}
+
+impl<S: SerializeStruct> SerializeStruct for ShapeStruct<'_, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error> {
+        match self.shape.get(key).filter(|_| self.stamp != Some(key)) {
+            Some(shape) => self.inner.serialize_field(key, &Part::new(value, shape)),
+            None => self.inner.skip_field(key),
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.inner.end()
+    }
+}
+
+struct ShapeMap<'a, S> {
+    inner: S,
+    shape: &'a Table,
+    // The part of the shape for the value of the last key, if it's getting written
+    part: Option<&'a Value>,
+}
+
+impl<S: SerializeMap> SerializeMap for ShapeMap<'_, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), S::Error> {
+        self.part = value::key_string(key).and_then(|key| self.shape.get(&key));
+        match self.part {
+            Some(_) => self.inner.serialize_key(key),
+            None => Ok(()),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        match self.part.take() {
+            Some(shape) => self.inner.serialize_value(&Part::new(value, shape)),
+            None => Ok(()),
+        }
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.inner.end()
+    }
+}
@@ -5,7 +5,7 @@ use crate::{Deserialize, Serialize};
 use log::LevelFilter;
 
 // Sub-data
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct SubData {
     pub string: String,
     pub unsigned: u64,
@@ -13,7 +13,7 @@ pub struct SubData {
 }
 
 // Data
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct MyData {
     pub number: i32,
     pub subdata: SubData,
@@ -30,6 +30,14 @@ impl Default for MyData {
         }
     }
 }
+impl crate::Validate for MyData {
+    fn validate(&self) -> Result<(), Vec<crate::ValidationError>> {
+        match self.number < 100 {
+            true => Ok(()),
+            false => Err(vec![crate::ValidationError::new("number", "must be below 100")]),
+        }
+    }
+}
 
 #[test]
 fn run() {
@@ -45,10 +53,10 @@ fn run() {
         format: {
             // These test the format auto-picking
             // Chooses JSON by default when all features are enabled; in the normal library this would throw an error
-            #[cfg(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml"))] {
+            #[cfg(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron"))] {
                 Some(crate::ConfigFormat::JSON)
             }
-            #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml")))] {
+            #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron")))] {
                 None
             }
         },
@@ -135,7 +143,7 @@ fn advanced_test() {
             //));
 
             cases.push(Case::new(FormatFinder::Config(*format), pretty));
-            #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml")))] {
+            #[cfg(not(all(feature = "json", feature = "json5", feature = "toml", feature = "yaml", feature = "ron")))] {
                 cases.push(Case::new(FormatFinder::Feature, pretty));
             }
         }
@@ -234,3 +242,1201 @@ fn no_create_if_missing() {
     log::error!("md is {:?}", md);
     assert!(md.is_err(), "should not have created config file");
 }
+
+// Comments and formatting should survive a save
+#[test]
+#[cfg(feature = "toml")]
+fn toml_keeps_comments() {
+    let dir = std::env::temp_dir().join("fast_config_toml_keeps_comments");
+    let path = dir.join("config.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &path,
+        "# The number\nnumber = 20 # inline comment\n\n[subdata]\n# Who?\nstring = 'Joe Mama'\nunsigned = 400\nboolean = true\n",
+    )
+    .unwrap();
+
+    let mut config = Config::new(&path, MyData::default()).unwrap();
+    config.data.number = 42;
+    config.save().unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "# The number\nnumber = 42 # inline comment\n\n[subdata]\n# Who?\nstring = 'Joe Mama'\nunsigned = 400\nboolean = true\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// A value turning into a table should keep the file valid, with the comment above it kept
+#[test]
+#[cfg(feature = "toml")]
+fn toml_value_becomes_table() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Mode {
+        Off,
+        Level(u8),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Settings {
+        n: u8,
+        mode: Mode,
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_toml_value_becomes_table");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "n = 1\n# How loud\nmode = \"Off\"\n").unwrap();
+
+    let mut config = Config::new(&path, Settings { n: 0, mode: Mode::Off }).unwrap();
+    config.data.mode = Mode::Level(3);
+    config.save().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "n = 1\n# How loud\n[mode]\nLevel = 3\n");
+    drop(config);
+
+    let config = Config::new(&path, Settings { n: 0, mode: Mode::Off }).unwrap();
+    assert_eq!(config.data, Settings { n: 1, mode: Mode::Level(3) });
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Comments, anchors and quoting should survive a save
+#[test]
+#[cfg(feature = "yaml")]
+fn yaml_keeps_comments() {
+    let dir = std::env::temp_dir().join("fast_config_yaml_keeps_comments");
+    let path = dir.join("config.yaml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &path,
+        "# The number\nnumber: 20 # inline comment\n\nsubdata:\n  # Who?\n  string: 'Joe Mama'\n  unsigned: 400\n  boolean: true\n",
+    )
+    .unwrap();
+
+    let mut config = Config::new(&path, MyData::default()).unwrap();
+    config.data.number = 42;
+    config.data.subdata.boolean = false;
+    config.save().unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "# The number\nnumber: 42 # inline comment\n\nsubdata:\n  # Who?\n  string: 'Joe Mama'\n  unsigned: 400\n  boolean: false\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Enums holding data get written as YAML tags, which should load back
+#[test]
+#[cfg(feature = "yaml")]
+fn yaml_tagged_enum() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Mode {
+        Off,
+        Level(u8),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Settings {
+        mode: Mode,
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_yaml_tagged_enum");
+    let path = dir.join("config.yaml");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut config = Config::new(&path, Settings { mode: Mode::Off }).unwrap();
+    config.data.mode = Mode::Level(3);
+    config.save().unwrap();
+    assert!(std::fs::read_to_string(&path).unwrap().contains("!Level 3"));
+    drop(config);
+
+    let config = Config::new(&path, Settings { mode: Mode::Off }).unwrap();
+    assert_eq!(config.data.mode, Mode::Level(3));
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Comments, trailing commas and unquoted keys should survive a save
+#[test]
+#[cfg(feature = "json5")]
+fn json5_keeps_comments() {
+    let dir = std::env::temp_dir().join("fast_config_json5_keeps_comments");
+    let path = dir.join("config.json5");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &path,
+        "{\n  // The number\n  number: 20, /* inline comment */\n  subdata: {\n    string: 'Joe Mama',\n    unsigned: 400,\n    boolean: true,\n  },\n}\n",
+    )
+    .unwrap();
+
+    let options = ConfigSetupOptions {
+        format: Some(crate::ConfigFormat::JSON5),
+        ..Default::default()
+    };
+    let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+    config.data.number = 42;
+    config.save().unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "{\n  // The number\n  number: 42, /* inline comment */\n  subdata: {\n    string: 'Joe Mama',\n    unsigned: 400,\n    boolean: true,\n  },\n}\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// A user-defined format should be picked by extension and go through `Value`
+#[test]
+fn custom_format() {
+    use crate::{ConfigFormatBackend, Value};
+
+    // `key = value` lines, nested tables get dotted keys
+    struct DottedFormat;
+
+    fn flatten(prefix: &str, value: &Value, output: &mut String) -> Result<(), String> {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table.iter() {
+                    flatten(&format!("{prefix}{key}."), value, output)?;
+                }
+            }
+            Value::String(string) => *output += &format!("{} = \"{string}\"\n", prefix.trim_end_matches('.')),
+            Value::Integer(number) => *output += &format!("{} = {number}\n", prefix.trim_end_matches('.')),
+            Value::Bool(boolean) => *output += &format!("{} = {boolean}\n", prefix.trim_end_matches('.')),
+            other => return Err(format!("Unsupported type: {}", other.type_name())),
+        }
+        Ok(())
+    }
+
+    impl ConfigFormatBackend for DottedFormat {
+        fn name(&self) -> &'static str { "dotted" }
+        fn extensions(&self) -> &[&str] { &["dotted"] }
+        fn supports_pretty(&self) -> bool { false }
+
+        fn serialize(&self, value: &Value, pretty: bool) -> Result<String, String> {
+            // The `pretty` option is on by default, but this format has no pretty form
+            if pretty {
+                return Err("Asked for pretty output".to_string());
+            }
+            let mut output = String::new();
+            flatten("", value, &mut output)?;
+            Ok(output)
+        }
+
+        fn deserialize(&self, text: &str) -> Result<Value, String> {
+            let mut root = Value::Table(Default::default());
+            for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                let (path, raw) = line.split_once(" = ").ok_or("Missing `=`")?;
+                let value = if let Some(string) = raw.strip_prefix('"') {
+                    Value::String(string.trim_end_matches('"').to_string())
+                } else if let Ok(boolean) = raw.parse() {
+                    Value::Bool(boolean)
+                } else {
+                    Value::Integer(raw.parse().map_err(|_| format!("Invalid value: {raw}"))?)
+                };
+
+                let mut keys: Vec<&str> = path.split('.').collect();
+                let last = keys.pop().ok_or("Empty key")?;
+                let mut table = root.as_table_mut().unwrap();
+                for key in keys {
+                    if !table.contains_key(key) {
+                        table.insert(key, Value::Table(Default::default()));
+                    }
+                    table = table.get_mut(key).and_then(Value::as_table_mut).ok_or("Not a table")?;
+                }
+                table.insert(last, value);
+            }
+            Ok(root)
+        }
+    }
+
+    let format = crate::register_format(DottedFormat).unwrap();
+    assert_eq!(format, crate::ConfigFormat::Custom("dotted"));
+    assert!(crate::register_format(DottedFormat).is_some(), "re-registering should replace it");
+
+    let dir = std::env::temp_dir().join("fast_config_custom_format");
+    let path = dir.join("config.dotted");
+    let _ = std::fs::remove_dir_all(&dir);
+    {
+        let mut config = Config::new(&path, MyData::default()).unwrap();
+        config.data.subdata.unsigned = 5;
+        config.save().unwrap();
+    }
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "number = 20\nsubdata.string = \"Joe Mama\"\nsubdata.unsigned = 5\nsubdata.boolean = true\n"
+    );
+
+    let config = Config::new(&path, MyData::default()).unwrap();
+    assert_eq!(config.data.subdata.unsigned, 5);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Configs that aren't backed by a file
+#[test]
+#[cfg(feature = "toml")]
+fn in_memory() {
+    let content = "# The number\nnumber = 20\n\n[subdata]\nstring = 'Joe Mama'\nunsigned = 400\nboolean = true\n";
+    let mut config: Config<MyData> = Config::from_str(content, crate::ConfigFormat::TOML).unwrap();
+    assert_eq!(config.data, MyData::default());
+    assert_eq!(config.filename(), "");
+
+    config.data.number = 42;
+    assert_eq!(config.to_string().unwrap(), content.replace("20", "42"));
+    assert!(matches!(config.save(), Err(crate::error::ConfigSaveError::MissingPath)));
+
+    let options = ConfigSetupOptions {
+        format: Some(crate::ConfigFormat::TOML),
+        ..Default::default()
+    };
+    let config = Config::empty(options, MyData::default()).unwrap();
+    let parsed: Config<MyData> = Config::from_str(&config.to_string().unwrap(), crate::ConfigFormat::TOML).unwrap();
+    assert_eq!(parsed.data, MyData::default());
+
+    assert!(Config::<MyData>::from_str("number = ", crate::ConfigFormat::TOML).is_err());
+}
+
+// Saving shouldn't leave temporary files behind, with or without `atomic_save`
+#[test]
+fn atomic_save() {
+    let dir = std::env::temp_dir().join("fast_config_atomic_save");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    for atomic_save in [true, false] {
+        let options = ConfigSetupOptions {
+            format: Some(crate::ConfigFormat::default()),
+            atomic_save,
+            ..Default::default()
+        };
+        let path = dir.join(format!("config-{atomic_save}"));
+        let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+        config.save().unwrap();
+        config.data.number = 42;
+        config.save().unwrap();
+
+        let config = Config::from_options(&path, options, MyData::default()).unwrap();
+        assert_eq!(config.data.number, 42);
+    }
+
+    // Saves of the same file running at the same time shouldn't get in each other's way
+    let path = dir.join("concurrent");
+    let threads: Vec<_> = (0..8)
+        .map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || (0..20).try_for_each(|_| crate::utils::write_atomic(&path, &i.to_string())))
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap().unwrap();
+    }
+
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(files.len(), 3, "unexpected files: {files:?}");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Saving should rotate backups, which can then be restored
+#[test]
+fn backups() {
+    use crate::{BackupNaming, BackupOptions};
+
+    let dir = std::env::temp_dir().join("fast_config_backups");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    for naming in [BackupNaming::Numbered, BackupNaming::Timestamped] {
+        let options = ConfigSetupOptions {
+            format: Some(crate::ConfigFormat::default()),
+            backups: Some(BackupOptions { keep: 2, naming }),
+            ..Default::default()
+        };
+        let path = dir.join(format!("config-{naming:?}"));
+        let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+        for number in 1..=4 {
+            config.data.number = number;
+            config.save().unwrap();
+            // Keeping the timestamps apart
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        // Nothing changed, so no new backup
+        config.save().unwrap();
+
+        assert_eq!(config.backups().len(), 2);
+        config.restore_backup(1).unwrap();
+        assert_eq!(config.data.number, 3);
+        config.restore_backup(2).unwrap();
+        assert_eq!(config.data.number, 2);
+        assert!(config.restore_backup(3).is_err());
+        assert_eq!(config.data.number, 2);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Reloading should pick up outside changes, and keep the data if the file is broken
+#[test]
+#[cfg(feature = "toml")]
+fn reload() {
+    let dir = std::env::temp_dir().join("fast_config_reload");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut config = Config::new(&path, MyData::default()).unwrap();
+    assert!(config.reload().is_err(), "there is no file yet");
+    config.save().unwrap();
+    assert!(!config.reload().unwrap());
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, content.replace("number = 20", "number = 42")).unwrap();
+    assert!(config.reload().unwrap());
+    assert_eq!(config.data.number, 42);
+
+    std::fs::write(&path, "number = [").unwrap();
+    assert!(matches!(config.reload(), Err(crate::error::ConfigError::DataParseError(_))));
+    assert_eq!(config.data.number, 42);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Modifying the file should reload the data and call the callbacks
+#[test]
+#[cfg(feature = "watch")]
+fn watch() {
+    let dir = std::env::temp_dir().join("fast_config_watch");
+    let _ = std::fs::remove_dir_all(&dir);
+    let options = ConfigSetupOptions {
+        format: Some(crate::ConfigFormat::default()),
+        ..Default::default()
+    };
+    let config = Config::from_options(dir.join("config"), options, MyData::default()).unwrap();
+    config.save().unwrap();
+    let path = config.path.clone();
+
+    let watcher = crate::ConfigWatcher::new(config).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    watcher.on_change(move |old: &MyData, new: &MyData| {
+        sender.send((old.number, new.number)).unwrap();
+    });
+
+    // Callbacks should be able to use the config without deadlocking
+    let (read_sender, read_receiver) = std::sync::mpsc::channel();
+    let shared = watcher.config();
+    watcher.on_change(move |_, _| {
+        read_sender.send(shared.read().unwrap().data.number).unwrap();
+    });
+
+    // Saving the same data shouldn't count as a change
+    watcher.read().save().unwrap();
+
+    // Simulating an editor that truncates the file before writing to it
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, "").unwrap();
+    std::fs::write(&path, content.replace("20", "42")).unwrap();
+
+    let change = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+    assert_eq!(change, (20, 42));
+    assert_eq!(read_receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap(), 42);
+    assert_eq!(watcher.read().data.number, 42);
+    assert!(receiver.recv_timeout(std::time::Duration::from_millis(300)).is_err());
+    drop(watcher);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// A made-up set of environment variables
+#[cfg(feature = "toml")]
+fn test_vars(vars: &[(&str, &str)]) -> Vec<(std::ffi::OsString, std::ffi::OsString)> {
+    vars.iter().map(|(var, value)| (var.into(), value.into())).collect()
+}
+
+// Environment variables should override the data, without ever being saved
+#[test]
+#[cfg(feature = "toml")]
+fn env_overrides() {
+    let dir = std::env::temp_dir().join("fast_config_env_overrides");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let content = "number = 20\n\n[subdata]\nstring = 'Joe Mama'\nunsigned = 400\nboolean = true\n";
+    std::fs::write(&path, content).unwrap();
+
+    // Tests run in parallel and share the process' environment, so the variables are made up instead
+    let options = ConfigSetupOptions {
+        env_prefix: Some("FAST_CONFIG_ENV_TEST"),
+        ..Default::default()
+    };
+    let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+    config.options.env_vars = || test_vars(&[("FAST_CONFIG_ENV_TEST__NUMBER", "42"), ("FAST_CONFIG_ENV_TEST__SUBDATA__BOOLEAN", "false")]);
+    config.reload().unwrap();
+    assert_eq!(config.data.number, 42);
+    assert!(!config.data.subdata.boolean);
+
+    // Only the value changed in code gets saved
+    config.data.subdata.unsigned = 5;
+    config.save().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content.replace("400", "5"));
+
+    config.options.env_vars = || test_vars(&[("FAST_CONFIG_ENV_TEST__NUMBER", "not a number")]);
+    assert!(matches!(
+        config.reload(),
+        Err(crate::error::ConfigError::InvalidOverride(var, _)) if var == "FAST_CONFIG_ENV_TEST__NUMBER"
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Layers should be deep-merged, with only the differences saved into the last one
+#[test]
+#[cfg(all(feature = "toml", feature = "json"))]
+fn layers() {
+    use crate::{ConfigFormat, Layer};
+
+    let dir = std::env::temp_dir().join("fast_config_layers");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("system.toml"), "number = 1\n\n[subdata]\nstring = 'System'\n").unwrap();
+    std::fs::write(dir.join("user.conf"), "{ \"subdata\": { \"unsigned\": 7 } }").unwrap();
+
+    let layers = vec![
+        Layer::new(dir.join("system.toml")),
+        Layer::new(dir.join("missing.toml")),
+        Layer::with_format(dir.join("user.conf"), ConfigFormat::JSON),
+    ];
+    let options = ConfigSetupOptions {
+        pretty: false,
+        ..Default::default()
+    };
+    let mut config = Config::from_layers(layers, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 1);
+    assert_eq!(config.data.subdata.string, "System");
+    assert_eq!(config.data.subdata.unsigned, 7);
+    assert!(config.data.subdata.boolean);
+
+    config.data.number = 5;
+    config.save().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("user.conf")).unwrap(),
+        "{\"number\":5,\"subdata\":{\"unsigned\":7}}"
+    );
+
+    // Changes to the lower layers get picked up when reloading
+    std::fs::write(dir.join("system.toml"), "[subdata]\nstring = 'Changed'\n").unwrap();
+    assert!(config.reload().unwrap());
+    assert_eq!(config.data.subdata.string, "Changed");
+    assert_eq!(config.data.number, 5);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Every value should know whether it came from a file, the environment, the defaults or the program
+#[test]
+#[cfg(feature = "toml")]
+fn provenance() {
+    use crate::{Layer, Source};
+
+    let dir = std::env::temp_dir().join("fast_config_provenance");
+    let path = dir.join("system.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "\n[subdata]\nstring = 'System'\n").unwrap();
+    std::fs::write(dir.join("user.toml"), "number = 2\n").unwrap();
+
+    let options = ConfigSetupOptions {
+        env_prefix: Some("FAST_CONFIG_SOURCE_TEST"),
+        ..Default::default()
+    };
+    let mut config = Config::from_layers(vec![Layer::new(&path), Layer::new(dir.join("user.toml"))], options, MyData::default()).unwrap();
+    config.options.env_vars = || test_vars(&[("FAST_CONFIG_SOURCE_TEST__NUMBER", "3")]);
+    config.reload().unwrap();
+
+    assert_eq!(
+        config.source_of("subdata.string"),
+        Some(Source::File { path: path.clone(), line: Some(3), column: Some(1) })
+    );
+    assert_eq!(config.source_of("number"), Some(Source::Env("FAST_CONFIG_SOURCE_TEST__NUMBER".into())));
+    assert_eq!(config.source_of("subdata.unsigned"), Some(Source::Default));
+    assert_eq!(config.source_of("subdata"), None);
+    assert_eq!(config.source_of("missing"), None);
+
+    config.data.subdata.unsigned = 1;
+    assert_eq!(config.source_of("subdata.unsigned"), Some(Source::Programmatic));
+    assert_eq!(config.sources().len(), 4);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Configs should be found in the XDG directories, with system-wide files only used as a base
+#[test]
+#[cfg(all(feature = "toml", unix, not(target_os = "macos")))]
+fn for_app() {
+    let dir = std::env::temp_dir().join("fast_config_for_app");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("system/fast_config_test")).unwrap();
+    std::fs::write(dir.join("system/fast_config_test/settings.toml"), "number = 1\n").unwrap();
+    std::fs::write(dir.join("system/fast_config_test/settings.bak"), "").unwrap();
+
+    let user = dir.join("user");
+    let system = format!("relative:{}", dir.join("system").display());
+    let var = |name: &str| match name {
+        "XDG_CONFIG_HOME" => Some(user.clone().into_os_string()),
+        "XDG_CONFIG_DIRS" => Some(system.clone().into()),
+        _ => None,
+    };
+    let dirs = crate::dirs::config_dirs_with("org", "fast_config_test", var);
+    assert_eq!(dirs, vec![dir.join("user/fast_config_test"), dir.join("system/fast_config_test")]);
+
+    let mut config = Config::from_dirs(dirs.clone(), "settings", ConfigSetupOptions::default(), MyData::default()).unwrap();
+    assert_eq!(config.data.number, 1);
+    assert_eq!(config.path, dir.join("user/fast_config_test/settings.toml"));
+    config.data.subdata.unsigned = 5;
+    config.save().unwrap();
+    drop(config);
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("user/fast_config_test/settings.toml")).unwrap(),
+        "[subdata]\nunsigned = 5\n"
+    );
+
+    // The user's file gets read on top of the system-wide one from now on
+    let config = Config::from_dirs(dirs, "settings", ConfigSetupOptions::default(), MyData::default()).unwrap();
+    assert_eq!(config.data.subdata.unsigned, 5);
+    assert_eq!(config.data.number, 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Project-local configs should be found up to the repository root, and merged nearest last
+#[test]
+#[cfg(all(feature = "toml", feature = "json"))]
+fn discover() {
+    let dir = std::env::temp_dir().join("fast_config_discover");
+    let repo = dir.join("repo");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(repo.join(".git")).unwrap();
+    std::fs::create_dir_all(repo.join("a/b")).unwrap();
+    std::fs::write(dir.join(".myapp.toml"), "number = 99\n").unwrap();
+    std::fs::write(repo.join(".myapp.toml"), "number = 1\n\n[subdata]\nstring = 'Repo'\nunsigned = 3\nboolean = false\n").unwrap();
+    std::fs::write(repo.join("a/.myapp.json"), "{ \"number\": 2 }").unwrap();
+
+    assert_eq!(
+        crate::dirs::discover(repo.join("a/b"), ".myapp"),
+        vec![repo.join("a/.myapp.json"), repo.join(".myapp.toml")]
+    );
+
+    let config = Config::discover(&repo, ".myapp", ConfigSetupOptions::default(), MyData::default()).unwrap();
+    assert_eq!(config.path, repo.join(".myapp.toml"));
+    assert_eq!(config.data.number, 1);
+    drop(config);
+
+    let config = Config::discover_merged(repo.join("a/b"), ".myapp", ConfigSetupOptions::default(), MyData::default()).unwrap();
+    assert_eq!(config.path, repo.join("a/.myapp.json"));
+    assert_eq!(config.data.number, 2);
+    assert_eq!(config.data.subdata.string, "Repo");
+    drop(config);
+
+    // Nothing found, so the config goes where the search started
+    let options = ConfigSetupOptions {
+        format: Some(crate::ConfigFormat::TOML),
+        ..Default::default()
+    };
+    let config = Config::discover(repo.join("a/b"), ".other", options, MyData::default()).unwrap();
+    assert_eq!(config.path, repo.join("a/b/.other.toml"));
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Relative starts should be searched from the current directory upwards, not only within the path as given
+#[test]
+#[cfg(feature = "toml")]
+fn discover_relative() {
+    let dir = std::env::temp_dir().join("fast_config_discover_relative");
+
+    // The current directory is shared by every test, so the search runs in a child process
+    if std::env::var_os("FAST_CONFIG_DISCOVER_CHILD").is_some() {
+        let found = crate::dirs::discover(".", ".myapp");
+        assert_eq!(found, vec![dir.canonicalize().unwrap().join(".myapp.toml")]);
+        let config = Config::discover(".", ".myapp", ConfigSetupOptions::default(), MyData::default()).unwrap();
+        assert_eq!(config.data.number, 7);
+        return;
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join(".myapp.toml"), "number = 7\n\n[subdata]\nstring = 'Joe'\nunsigned = 3\nboolean = false\n").unwrap();
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "tests::discover_relative"])
+        .current_dir(dir.canonicalize().unwrap().join("nested"))
+        .env("FAST_CONFIG_DISCOVER_CHILD", "1")
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Old files should be migrated before being deserialized, then rewritten with the original kept aside
+#[test]
+#[cfg(feature = "toml")]
+fn migrations() {
+    use crate::{Migrations, Value};
+
+    fn rename_num(value: &mut Value) -> Result<(), String> {
+        let table = value.as_table_mut().ok_or("Not a table")?;
+        let num = table.remove("num").ok_or("Missing `num`")?;
+        table.insert("number", num);
+        Ok(())
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_migrations");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let original = "num = 7\n\n[subdata]\nstring = 'Old'\nunsigned = 1\nboolean = false\n";
+    std::fs::write(&path, original).unwrap();
+
+    let options = ConfigSetupOptions {
+        migrations: Some(Migrations::new(&[rename_num])),
+        ..Default::default()
+    };
+    let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 7);
+    assert_eq!(config.migrated_from(), Some(1));
+
+    config.save().unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("config.toml.v1")).unwrap(), original);
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("version = 2") && saved.contains("number = 7") && !saved.contains("num ="));
+
+    assert!(!config.reload().unwrap());
+    assert_eq!(config.migrated_from(), None);
+
+    // Files from a newer version of the program are refused
+    std::fs::write(&path, saved.replace("version = 2", "version = 3")).unwrap();
+    assert!(matches!(config.reload(), Err(crate::error::ConfigError::Migration(3, _))));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// RON files written through `Value` (migrations, layers) should still use struct and enum syntax
+#[test]
+#[cfg(feature = "ron")]
+fn ron_keeps_structs() {
+    use crate::{Layer, Migrations};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Theme {
+        Light,
+        Dark,
+        Custom(String),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Window {
+        width: u32,
+        height: u32,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Settings {
+        theme: Theme,
+        window: Window,
+    }
+
+    let defaults = || Settings { theme: Theme::Light, window: Window { width: 800, height: 600 } };
+
+    let dir = std::env::temp_dir().join("fast_config_ron_keeps_structs");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Struct names are optional
+    std::fs::write(dir.join("config.ron"), "// Old\n(theme: Dark, window: Window(width: 640, height: 480))").unwrap();
+    let options = ConfigSetupOptions {
+        pretty: false,
+        migrations: Some(Migrations::new(&[])),
+        ..Default::default()
+    };
+    let mut config = Config::from_options(dir.join("config.ron"), options, defaults()).unwrap();
+    assert_eq!(config.data, Settings { theme: Theme::Dark, window: Window { width: 640, height: 480 } });
+    config.data.theme = Theme::Custom("Solarized".into());
+    config.save().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("config.ron")).unwrap(),
+        "(version:1,theme:Custom(\"Solarized\"),window:(width:640,height:480))"
+    );
+    assert!(!config.reload().unwrap());
+    assert_eq!(config.data.theme, Theme::Custom("Solarized".into()));
+
+    // Only the differences get written into the last layer, in the same syntax
+    std::fs::write(dir.join("system.ron"), "(window: (width: 1024))").unwrap();
+    let layers = vec![Layer::new(dir.join("system.ron")), Layer::new(dir.join("user.ron"))];
+    let options = ConfigSetupOptions {
+        pretty: false,
+        ..Default::default()
+    };
+    let mut config = Config::from_layers(layers, options, defaults()).unwrap();
+    assert_eq!(config.data.window.width, 1024);
+    config.data.theme = Theme::Dark;
+    config.data.window.height = 768;
+    config.save().unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("user.ron")).unwrap(), "(theme:Dark,window:(height:768))");
+    assert!(!config.reload().unwrap());
+    assert_eq!(config.data, Settings { theme: Theme::Dark, window: Window { width: 1024, height: 768 } });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// TOML datetimes should survive being saved through `Value` (here, because of migrations)
+#[test]
+#[cfg(feature = "toml")]
+fn toml_datetime_migrated() {
+    use crate::{Migrations, Value};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Settings {
+        when: toml::value::Datetime,
+        n: u8,
+    }
+
+    fn nothing(_: &mut Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_toml_datetime_migrated");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "# hi\nwhen = 1979-05-27T07:32:00Z\nn = 1\n").unwrap();
+
+    let options = ConfigSetupOptions {
+        migrations: Some(Migrations::new(&[nothing])),
+        ..Default::default()
+    };
+    let when: toml::value::Datetime = "1979-05-27T07:32:00Z".parse().unwrap();
+    let mut config = Config::from_options(&path, options, Settings { when: "2000-01-01T00:00:00Z".parse().unwrap(), n: 0 }).unwrap();
+    assert_eq!(config.data.when, when);
+    config.data.n = 2;
+    config.save().unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("# hi\nwhen = 1979-05-27T07:32:00Z\n") && saved.contains("version = 2"), "{saved}");
+
+    assert!(!config.reload().unwrap());
+    assert_eq!(config.data, Settings { when, n: 2 });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Fields missing from an existing file should get written into it, leaving the rest alone
+#[test]
+#[cfg(feature = "toml")]
+fn write_defaults() {
+    #[derive(Serialize, Deserialize, Default)]
+    struct Settings {
+        name: String,
+        #[serde(default)]
+        retries: u32,
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_write_defaults");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "# Who's running this\nname = 'Joe'\n").unwrap();
+
+    let options = ConfigSetupOptions {
+        write_defaults: true,
+        ..Default::default()
+    };
+    let config = Config::from_options(&path, options, Settings::default()).unwrap();
+    assert_eq!(config.data.name, "Joe");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# Who's running this\nname = 'Joe'\nretries = 0\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Keys the data has no field for should be listed, or refused in strict mode
+#[test]
+#[cfg(feature = "toml")]
+fn unknown_keys() {
+    use crate::UnknownKeys;
+
+    let dir = std::env::temp_dir().join("fast_config_unknown_keys");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let content = "number = 1\nnumbr = 2\n\n[subdata]\nstring = 'Joe'\nunsigned = 3\nboolean = true\n\n[subdata.extra]\nkey = 4\n";
+    std::fs::write(&path, content).unwrap();
+
+    let options = ConfigSetupOptions {
+        unknown_keys: UnknownKeys::Warn,
+        ..Default::default()
+    };
+    let config = Config::from_options(&path, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 1);
+    assert_eq!(config.unknown_keys(), ["numbr", "subdata.extra"]);
+    drop(config);
+
+    let options = ConfigSetupOptions {
+        unknown_keys: UnknownKeys::Deny,
+        ..Default::default()
+    };
+    let result = Config::from_options(&path, options, MyData::default());
+    assert!(matches!(result, Err(crate::error::ConfigError::UnknownKeys(keys)) if keys.len() == 2));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Invalid data should never be loaded, and never replace the file on the disk
+#[test]
+#[cfg(feature = "toml")]
+fn validation() {
+    let dir = std::env::temp_dir().join("fast_config_validation");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let content = "number = 1\n\n[subdata]\nstring = 'Joe'\nunsigned = 3\nboolean = true\n";
+    std::fs::write(&path, content).unwrap();
+
+    let mut config = Config::new(&path, MyData::default()).and_then(Config::validated).unwrap();
+    config.data.number = 500;
+    assert!(matches!(
+        config.save(),
+        Err(crate::error::ConfigSaveError::Validation(errors)) if errors[0].path == "number"
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+    std::fs::write(&path, content.replace("number = 1", "number = 200")).unwrap();
+    config.data.number = 2;
+    assert!(matches!(config.reload(), Err(crate::error::ConfigError::Validation(_))));
+    assert_eq!(config.data.number, 2);
+    drop(config);
+
+    assert!(Config::new(&path, MyData::default()).and_then(Config::validated).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Constructing a validated config shouldn't write data that fails the checks into the file
+#[test]
+#[cfg(feature = "toml")]
+fn validation_before_write_defaults() {
+    use crate::{Validate, ValidationError};
+
+    #[derive(Serialize, Deserialize, Default)]
+    struct Settings {
+        port: u32,
+        #[serde(default)]
+        extra: u32,
+    }
+    impl Validate for Settings {
+        fn validate(&self) -> Result<(), Vec<ValidationError>> {
+            match self.port < 100 {
+                true => Ok(()),
+                false => Err(vec![ValidationError::new("port", "must be below 100")]),
+            }
+        }
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_validation_before_write_defaults");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "port = 500\n").unwrap();
+
+    let options = ConfigSetupOptions {
+        write_defaults: true,
+        ..Default::default()
+    };
+    let result = Config::from_options_validated(&path, options, Settings::default());
+    assert!(matches!(result, Err(crate::error::ConfigError::Validation(_))));
+    assert_eq!(std::fs::read(&path).unwrap(), b"port = 500\n");
+
+    // Valid data still gets its defaults written
+    std::fs::write(&path, "port = 50\n").unwrap();
+    Config::from_options_validated(&path, options, Settings::default()).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "port = 50\nextra = 0\n");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// The schema should be written next to the config, and referenced from the saved files
+#[test]
+#[cfg(all(feature = "schema", feature = "toml", feature = "json"))]
+fn schema() {
+    #[derive(Serialize, Deserialize, schemars::JsonSchema)]
+    struct Settings {
+        port: u16,
+    }
+
+    let dir = std::env::temp_dir().join("fast_config_schema");
+    let _ = std::fs::remove_dir_all(&dir);
+    let options = ConfigSetupOptions {
+        schema: Some("settings.schema.json"),
+        ..Default::default()
+    };
+
+    let config = Config::from_options(dir.join("settings.toml"), options, Settings { port: 80 }).unwrap();
+    let schema_path = config.write_schema().unwrap();
+    assert_eq!(schema_path, dir.join("settings.schema.json"));
+    assert!(std::fs::read_to_string(&schema_path).unwrap().contains("\"port\""));
+    config.save().unwrap();
+    config.save().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("settings.toml")).unwrap(),
+        "#:schema settings.schema.json\nport = 80\n"
+    );
+
+    let options = ConfigSetupOptions {
+        pretty: false,
+        ..options
+    };
+    let config = Config::from_options(dir.join("settings.json"), options, Settings { port: 80 }).unwrap();
+    config.save().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("settings.json")).unwrap(),
+        "{\"$schema\":\"settings.schema.json\",\"port\":80}"
+    );
+    drop(config);
+
+    // The reference isn't part of the data, so it doesn't count as an unknown key
+    let options = ConfigSetupOptions {
+        unknown_keys: crate::UnknownKeys::Deny,
+        ..options
+    };
+    let config = Config::from_options(dir.join("settings.json"), options, Settings { port: 1 }).unwrap();
+    assert_eq!(config.data.port, 80);
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Parse errors should say where they happened, and render the line they're on
+#[test]
+fn parse_errors() {
+    use crate::error::{ConfigError, DataParseError, DeserializeError};
+    use crate::ConfigFormat;
+
+    #[allow(dead_code)]
+    fn parse_error(content: &str, format: ConfigFormat) -> DeserializeError {
+        match Config::<MyData>::from_str(content, format) {
+            Err(ConfigError::DataParseError(DataParseError::Deserialize(_, error))) => *error,
+            _ => panic!("\"{content}\" should fail to parse as {format}"),
+        }
+    }
+    #[allow(dead_code)]
+    fn error_line(content: &str, format: ConfigFormat) -> Option<usize> {
+        parse_error(content, format).span.map(|span| span.line)
+    }
+
+    #[cfg(feature = "toml")] {
+        let error = parse_error("number = 1\n\n[subdata]\nstring = 'Joe'\nunsigned = 'lots'\n", ConfigFormat::TOML);
+        assert_eq!(error.to_string(), "5:12: invalid type: string \"lots\", expected u64");
+        assert_eq!(
+            error.render(),
+            "error: invalid type: string \"lots\", expected u64\n --> 5:12\n  |\n5 | unsigned = 'lots'\n  |            ^^^^^^"
+        );
+    }
+    #[cfg(feature = "json")]
+    assert_eq!(error_line("{\n  \"number\": 1,\n  \"subdata\": {\n    \"unsigned\": \"lots\" } }", ConfigFormat::JSON), Some(4));
+    #[cfg(feature = "json5")]
+    assert_eq!(error_line("{\n  number: 1,\n  subdata: {\n    unsigned: 'lots' } }", ConfigFormat::JSON5), Some(4));
+    #[cfg(feature = "yaml")]
+    assert_eq!(error_line("number: 1\nsubdata:\n  string: Joe\n  unsigned: lots\n", ConfigFormat::YAML), Some(4));
+    #[cfg(feature = "ron")]
+    assert_eq!(error_line("(\n    number: 1,\n    subdata: (\n        unsigned: \"lots\",\n    ),\n)", ConfigFormat::RON), Some(4));
+}
+
+// Errors should tell what kind of problem they are, and keep the parser's error as their source
+#[test]
+fn error_kinds() {
+    use crate::error::ErrorKind;
+    use crate::ConfigFormat;
+
+    #[allow(dead_code)]
+    fn kind(content: &str, format: ConfigFormat) -> ErrorKind {
+        match Config::<MyData>::from_str(content, format) {
+            Err(error) => error.kind(),
+            Ok(_) => panic!("\"{content}\" should fail to parse as {format}"),
+        }
+    }
+
+    #[cfg(feature = "json")] {
+        let error = Config::<MyData>::from_str("{ \"number\": ", ConfigFormat::JSON).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Syntax);
+        let parser_error = std::error::Error::source(&error).and_then(std::error::Error::source).unwrap();
+        assert!(parser_error.downcast_ref::<serde_json::Error>().is_some());
+
+        assert_eq!(kind("{ \"number\": \"one\" }", ConfigFormat::JSON), ErrorKind::TypeMismatch);
+        assert_eq!(kind("{ \"number\": 1 }", ConfigFormat::JSON), ErrorKind::MissingField);
+
+        let content = "{ \"number\": 1, \"subdata\": { \"string\": \"Joe\", \"unsigned\": 1, \"boolean\": true } }";
+        let config = Config::<MyData>::from_str(content, ConfigFormat::JSON).unwrap();
+        assert_eq!(config.save().err().unwrap().kind(), ErrorKind::MissingPath);
+    }
+    #[cfg(feature = "toml")] {
+        let error = Config::<MyData>::from_str("number = 1\n[subdata", ConfigFormat::TOML).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Syntax);
+        let parser_error = std::error::Error::source(&error).and_then(std::error::Error::source).unwrap();
+        assert!(parser_error.downcast_ref::<toml::de::Error>().is_some());
+
+        assert_eq!(kind("number = 'one'", ConfigFormat::TOML), ErrorKind::TypeMismatch);
+        assert_eq!(kind("number = 1", ConfigFormat::TOML), ErrorKind::MissingField);
+    }
+    #[cfg(feature = "yaml")] {
+        assert_eq!(kind("number: [1", ConfigFormat::YAML), ErrorKind::Syntax);
+        assert_eq!(kind("number: one", ConfigFormat::YAML), ErrorKind::TypeMismatch);
+        assert_eq!(kind("number: 1", ConfigFormat::YAML), ErrorKind::MissingField);
+    }
+    #[cfg(feature = "ron")] {
+        assert_eq!(kind("(number: 1", ConfigFormat::RON), ErrorKind::Syntax);
+        assert_eq!(kind("(number: \"one\")", ConfigFormat::RON), ErrorKind::TypeMismatch);
+        assert_eq!(kind("(number: 1)", ConfigFormat::RON), ErrorKind::MissingField);
+    }
+}
+
+// Broken config files should get moved aside, keeping what can be kept of them when salvaging
+#[test]
+#[cfg(feature = "toml")]
+fn recovery() {
+    use crate::error::ErrorKind;
+    use crate::Recovery;
+
+    let dir = std::env::temp_dir().join("fast_config_recovery");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let corrupt_files = || {
+        std::fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().starts_with("config.toml.corrupt-"))
+            .count()
+    };
+
+    // Failing by default
+    std::fs::write(&path, "number = 'five'\n\n[subdata]\nstring = 'Joe'\nunsigned = 'lots'\nboolean = false\n").unwrap();
+    assert!(Config::new(&path, MyData::default()).is_err());
+    assert_eq!(corrupt_files(), 0);
+
+    // Keeping the fields that still work
+    let options = ConfigSetupOptions {
+        recovery: Recovery::Salvage,
+        ..Default::default()
+    };
+    let config = Config::from_options(&path, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 20);
+    assert_eq!(config.data.subdata.string, "Joe");
+    assert_eq!(config.data.subdata.unsigned, 400);
+    assert!(!config.data.subdata.boolean);
+    let recovered = config.recovered_from().unwrap();
+    assert_eq!(recovered.error.kind(), ErrorKind::TypeMismatch);
+    assert_eq!(recovered.reset_keys, vec!["number", "subdata.unsigned"]);
+    assert!(recovered.moved_to.is_file());
+    assert!(!path.exists());
+    config.save().unwrap();
+    assert!(Config::new(&path, MyData::default()).unwrap().recovered_from().is_none());
+
+    // Starting over when there's nothing to salvage
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    std::fs::write(&path, "number = [1\n").unwrap();
+    let config = Config::from_options(&path, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 20);
+    assert_eq!(config.data.subdata.string, "Joe Mama");
+    assert!(config.recovered_from().unwrap().reset_keys.is_empty());
+    assert_eq!(corrupt_files(), 2);
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Fields that fail to deserialize should keep their defaults, without failing the rest of the file
+#[test]
+#[cfg(feature = "toml")]
+fn lenient() {
+    let dir = std::env::temp_dir().join("fast_config_lenient");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let content = "# Turned up\nnumber = 'loud'\n\n[subdata]\nstring = 'Joe'\nunsigned = 3\nboolean = false\n";
+    std::fs::write(&path, content).unwrap();
+
+    let options = ConfigSetupOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let mut config = Config::from_options(&path, options, MyData::default()).unwrap();
+    assert_eq!(config.data.number, 20);
+    assert_eq!(config.data.subdata.unsigned, 3);
+    assert_eq!(config.reset_keys(), ["number"]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+    // Falling back to the defaults rather than to what was there before
+    std::fs::write(&path, "number = 5\n\n[subdata]\nstring = 'Joe'\nunsigned = -3\nboolean = false\n").unwrap();
+    assert!(config.reload().unwrap());
+    assert_eq!(config.data.number, 5);
+    assert_eq!(config.data.subdata.unsigned, 400);
+    assert_eq!(config.reset_keys(), ["subdata.unsigned"]);
+
+    // Missing fields get their default value too
+    std::fs::write(&path, "number = 5\n\n[subdata]\nstring = 'Joe'\nunsigned = 3\n").unwrap();
+    assert!(config.reload().unwrap());
+    assert!(config.data.subdata.boolean);
+    assert_eq!(config.reset_keys(), ["subdata.boolean"]);
+
+    // Syntax errors can't be worked around
+    std::fs::write(&path, "number = [5\n").unwrap();
+    assert!(config.reload().is_err());
+    drop(config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Values should be readable and writable by their key path, without going through the data's fields
+#[test]
+fn dotted_keys() {
+    use crate::error::{ConfigError, ErrorKind};
+    use crate::ConfigFormat;
+
+    #[derive(Serialize, Deserialize)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+    #[derive(Serialize, Deserialize)]
+    struct Settings {
+        person: Person,
+        nickname: Option<String>,
+        ports: Vec<u16>,
+    }
+
+    let options = ConfigSetupOptions {
+        format: Some(ConfigFormat::default()),
+        ..Default::default()
+    };
+    let data = Settings {
+        person: Person { name: "Joe".into(), age: 40 },
+        nickname: Some("Jo".into()),
+        ports: vec![80, 443],
+    };
+    let mut config = Config::empty(options, data).unwrap();
+    assert_eq!(config.keys(), ["person.name", "person.age", "nickname", "ports"]);
+    assert_eq!(config.get::<String>("person.name").unwrap(), Some("Joe".to_string()));
+    assert_eq!(config.get::<u16>("ports.1").unwrap(), Some(443));
+    assert_eq!(config.get::<u32>("person.height").unwrap(), None);
+    assert_eq!(config.get::<u32>("person.name").err().unwrap().kind(), ErrorKind::TypeMismatch);
+
+    config.set("person.age", 41).unwrap();
+    config.set("ports.0", 8080).unwrap();
+    assert_eq!(config.data.person.age, 41);
+    assert_eq!(config.data.ports, [8080, 443]);
+
+    // Failing without touching the data
+    let error = config.set("person.age", "old").err().unwrap();
+    assert!(matches!(&error, ConfigError::InvalidKey(key, _) if key == "person.age"));
+    assert_eq!(error.kind(), ErrorKind::TypeMismatch);
+    assert!(matches!(config.set("person.height", 180), Err(ConfigError::UnknownKeys(_))));
+    assert!(config.set("ports.5", 1).is_err());
+    assert_eq!(config.data.person.age, 41);
+
+    // Key paths with empty keys in them are refused
+    for path in ["", "person..age", "person.", ".person"] {
+        assert!(matches!(config.set(path, 1), Err(ConfigError::InvalidKey(key, _)) if key == path));
+        assert!(config.get::<u32>(path).is_err());
+        assert!(config.remove(path).is_err());
+    }
+
+    assert!(config.remove("nickname").unwrap());
+    assert_eq!(config.data.nickname, None);
+    assert!(!config.remove("person.height").unwrap());
+    assert_eq!(config.remove("person.name").err().unwrap().kind(), ErrorKind::MissingField);
+    assert_eq!(config.data.person.name, "Joe");
+}
@@ -1,3 +1,10 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
 // Gets rid of unnecessary lines
 #[cfg(feature = "yaml")]
 pub fn compress_string(string: String) -> String {
@@ -9,3 +16,83 @@ pub fn compress_string(string: String) -> String {
     }
     result
 }
+
+// Writes a file by writing into a temporary file next to it, then renaming it over the target.
+// A crash at any point leaves either the old or the new contents, never a half-written file.
+pub fn write_atomic(path: &Path, data: &str) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    // The counter keeps saves running at the same time in this process from sharing a temporary file
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    temp_name.push(format!(".tmp-{}-{count}", std::process::id()));
+    let temp_path = dir.join(temp_name);
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(data.as_bytes())?;
+
+        // Keeping the permissions of the file that gets replaced
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Making sure the rename itself is on the disk
+    // (Directories can't be opened as files on Windows, and NTFS journals renames anyway)
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
+
+// The current time (UTC) as `YYYYMMDD-HHMMSS-mmm`, used to name backup files
+pub fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs();
+    let (hour, minute, second) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    // Converting days since the epoch into a date (see http://howardhinnant.github.io/date_algorithms.html)
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}-{:03}",
+        now.subsec_millis()
+    )
+}
+
+// Checks if a string looks like the output of `timestamp`
+pub fn is_timestamp(string: &str) -> bool {
+    string.len() == 19
+        && string.char_indices().all(|(i, c)| match i {
+            8 | 15 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+// Converts a byte offset into a line and a column, both starting at 1
+pub fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
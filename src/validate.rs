@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+
+/// Semantic checks on your data that Serde can't express *(ex: "port must be between 1 and 65535")*. <br/>
+/// Enable them with [`Config::validated`](crate::Config::validated) or [`Config::from_options_validated`](crate::Config::from_options_validated),
+/// after which they run whenever the config gets loaded, reloaded or saved.
+///
+/// # Example:
+/// ```
+/// use fast_config::{Validate, ValidationError};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyData {
+///     pub port: u32,
+/// }
+///
+/// impl Validate for MyData {
+///     fn validate(&self) -> Result<(), Vec<ValidationError>> {
+///         let mut errors = Vec::new();
+///         if !(1..=65535).contains(&self.port) {
+///             errors.push(ValidationError::new("port", "must be between 1 and 65535"));
+///         }
+///         match errors.is_empty() {
+///             true => Ok(()),
+///             false => Err(errors),
+///         }
+///     }
+/// }
+/// ```
+pub trait Validate {
+    /// Checks the data, returning every problem found with it.
+    fn validate(&self) -> Result<(), Vec<ValidationError>>;
+}
+
+/// A problem with one of the fields of your data, found by [`Validate`]
+///
+/// # Attributes
+/// - `path` - The dotted path to the field *(ex: `"database.port"`)*
+/// - `message` - What's wrong with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+// Joins a list of errors into a single line
+pub(crate) fn describe(errors: &[ValidationError]) -> String {
+    errors.iter().map(ValidationError::to_string).collect::<Vec<_>>().join(", ")
+}
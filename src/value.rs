@@ -0,0 +1,1132 @@
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize};
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+
+// - This module holds a format-neutral tree of values.
+//   It's what custom formats (see `ConfigFormatBackend`) read and write,
+//   so they don't need to know anything about your data struct.
+// ------------------------------------------------------------------
+// Your data struct can be converted into a `Value` with `to_value`,
+// and back with `from_value`.
+
+/// A format-neutral config value
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+/// A map of keys to [`Value`]s, which keeps the order its keys were inserted in
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    entries: Vec<(String, Value)>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a value, returning the old one if the key was already present. <br/>
+    /// Existing keys keep their position, new keys get added at the end.
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        let key = key.into();
+        match self.get_mut(&key) {
+            Some(existing) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut Value)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl IntoIterator for Table {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl FromIterator<(String, Value)> for Table {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut table = Table::new();
+        for (key, value) in iter {
+            table.insert(key, value);
+        }
+        table
+    }
+}
+
+impl Value {
+    /// The name of the value's type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self { Value::Bool(value) => Some(*value), _ => None }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self { Value::Integer(value) => Some(*value), _ => None }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(value) => Some(*value),
+            Value::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self { Value::String(value) => Some(value), _ => None }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self { Value::Array(value) => Some(value), _ => None }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self { Value::Array(value) => Some(value), _ => None }
+    }
+
+    pub fn as_table(&self) -> Option<&Table> {
+        match self { Value::Table(value) => Some(value), _ => None }
+    }
+
+    pub fn as_table_mut(&mut self) -> Option<&mut Table> {
+        match self { Value::Table(value) => Some(value), _ => None }
+    }
+
+    /// Gets a value from a table by its key
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_table()?.get(key)
+    }
+
+    /// Gets a value from a table by its key
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_table_mut()?.get_mut(key)
+    }
+}
+
+/// An error that occurred while converting to/from a [`Value`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    pub fn new(message: impl Display) -> Self {
+        Self { message: message.to_string() }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Error::new(message)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Error::new(message)
+    }
+}
+
+/// Converts anything that implements [`Serialize`] into a [`Value`]
+pub fn to_value<T>(value: &T) -> Result<Value, Error>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(ValueSerializer)
+}
+
+/// Converts a [`Value`] into anything that implements [`Deserialize`](serde::Deserialize)
+pub fn from_value<T>(value: Value) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+// ---- Values by key path ----
+
+// Gets the paths to every value that isn't a table (as well as empty tables)
+pub(crate) fn leaves(value: &Value) -> Vec<Vec<String>> {
+    fn collect(value: &Value, path: &mut Vec<String>, output: &mut Vec<Vec<String>>) {
+        match value {
+            Value::Table(table) if !table.is_empty() => {
+                for (key, value) in table.iter() {
+                    path.push(key.clone());
+                    collect(value, path, output);
+                    path.pop();
+                }
+            }
+            _ => output.push(path.clone()),
+        }
+    }
+    let mut output = Vec::new();
+    if let Value::Table(table) = value {
+        for (key, value) in table.iter() {
+            collect(value, &mut vec![key.clone()], &mut output);
+        }
+    }
+    output
+}
+
+// Gets the value at `path`, going into tables by key and arrays by index
+pub(crate) fn get_at<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let mut current = value;
+    for key in path {
+        current = match current {
+            Value::Table(table) => table.get(key)?,
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+// Same as `get_at`, but mutable
+pub(crate) fn get_at_mut<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let mut current = value;
+    for key in path {
+        current = match current {
+            Value::Table(table) => table.get_mut(key)?,
+            Value::Array(items) => items.get_mut(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+// Puts `new` at `path`, going into tables by key and arrays by index.
+// Tables that don't exist yet get created on the way
+pub(crate) fn insert_at(value: &mut Value, path: &[String], new: Value) -> Result<(), String> {
+    let Some((key, rest)) = path.split_first() else {
+        *value = new;
+        return Ok(());
+    };
+    match value {
+        Value::Table(table) => {
+            if rest.is_empty() {
+                table.insert(key.clone(), new);
+                return Ok(());
+            }
+            if !table.contains_key(key) {
+                table.insert(key.clone(), Value::Table(Table::new()));
+            }
+            insert_at(table.get_mut(key).unwrap(), rest, new)
+        }
+        Value::Array(items) => {
+            let length = items.len();
+            let item = key.parse::<usize>().ok().and_then(|index| items.get_mut(index));
+            match item {
+                Some(item) => insert_at(item, rest, new),
+                None => Err(format!("\"{key}\" is not an index of the array (it has {length} items)")),
+            }
+        }
+        other => Err(format!("\"{key}\" can't be looked up in a {} value", other.type_name())),
+    }
+}
+
+// Takes out the value at `path`, if there is one
+pub(crate) fn remove_at(value: &mut Value, path: &[String]) -> Option<Value> {
+    let (last, parents) = path.split_last()?;
+    match get_at_mut(value, parents)? {
+        Value::Table(table) => table.remove(last),
+        Value::Array(items) => {
+            let index = last.parse::<usize>().ok().filter(|index| *index < items.len())?;
+            Some(items.remove(index))
+        }
+        _ => None,
+    }
+}
+
+// ---- Value -> any format ----
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::{SerializeMap, SerializeSeq};
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Integer(value) => {
+                // Most formats don't support 128-bit integers
+                if let Ok(value) = i64::try_from(*value) {
+                    serializer.serialize_i64(value)
+                } else if let Ok(value) = u64::try_from(*value) {
+                    serializer.serialize_u64(value)
+                } else {
+                    serializer.serialize_i128(*value)
+                }
+            }
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Value::Table(table) => {
+                let mut map = serializer.serialize_map(Some(table.len()))?;
+                for (key, value) in table.iter() {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+// ---- Any format -> Value ----
+
+impl<'de> de::Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("any valid config value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> { Ok(Value::Bool(value)) }
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> { Ok(Value::Integer(value.into())) }
+    fn visit_i128<E>(self, value: i128) -> Result<Value, E> { Ok(Value::Integer(value)) }
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> { Ok(Value::Integer(value.into())) }
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> { Ok(Value::Float(value)) }
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> { Ok(Value::String(value.to_string())) }
+    fn visit_string<E>(self, value: String) -> Result<Value, E> { Ok(Value::String(value)) }
+    fn visit_none<E>(self) -> Result<Value, E> { Ok(Value::Null) }
+    fn visit_unit<E>(self) -> Result<Value, E> { Ok(Value::Null) }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Value, E> {
+        i128::try_from(value).map(Value::Integer).map_err(|_| E::custom("integer is too large"))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Value, E> {
+        Ok(Value::Array(value.iter().map(|byte| Value::Integer((*byte).into())).collect()))
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        de::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        de::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut table = Table::new();
+        while let Some((key, value)) = map.next_entry::<Value, Value>()? {
+            let key = match key {
+                Value::String(key) => key,
+                Value::Integer(key) => key.to_string(),
+                Value::Bool(key) => key.to_string(),
+                Value::Float(key) => key.to_string(),
+                other => return Err(de::Error::custom(format!("unsupported {} map key", other.type_name()))),
+            };
+            table.insert(key, value);
+        }
+        Ok(Value::Table(table))
+    }
+}
+
+// ---- Data struct -> Value ----
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeTable;
+    type SerializeStruct = SerializeTable;
+    type SerializeStructVariant = SerializeVariant<SerializeTable>;
+
+    fn serialize_bool(self, value: bool) -> Result<Value, Error> { Ok(Value::Bool(value)) }
+    fn serialize_i8(self, value: i8) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_i16(self, value: i16) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_i32(self, value: i32) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_i64(self, value: i64) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_i128(self, value: i128) -> Result<Value, Error> { Ok(Value::Integer(value)) }
+    fn serialize_u8(self, value: u8) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_u16(self, value: u16) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_u32(self, value: u32) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_u64(self, value: u64) -> Result<Value, Error> { Ok(Value::Integer(value.into())) }
+    fn serialize_f32(self, value: f32) -> Result<Value, Error> { Ok(Value::Float(value.into())) }
+    fn serialize_f64(self, value: f64) -> Result<Value, Error> { Ok(Value::Float(value)) }
+    fn serialize_char(self, value: char) -> Result<Value, Error> { Ok(Value::String(value.to_string())) }
+    fn serialize_str(self, value: &str) -> Result<Value, Error> { Ok(Value::String(value.to_string())) }
+    fn serialize_none(self) -> Result<Value, Error> { Ok(Value::Null) }
+    fn serialize_unit(self) -> Result<Value, Error> { Ok(Value::Null) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> { Ok(Value::Null) }
+
+    fn serialize_u128(self, value: u128) -> Result<Value, Error> {
+        i128::try_from(value).map(Value::Integer).map_err(|_| Error::new("integer is too large"))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Array(value.iter().map(|byte| Value::Integer((*byte).into())).collect()))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, variant: &'static str, value: &T) -> Result<Value, Error> {
+        let mut table = Table::new();
+        table.insert(variant, to_value(value)?);
+        Ok(Value::Table(table))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(SerializeVariant { variant, inner: SerializeArray(Vec::with_capacity(len)) })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeTable, Error> {
+        Ok(SerializeTable { table: Table::new(), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<SerializeTable, Error> {
+        self.serialize_map(None)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(SerializeVariant { variant, inner: SerializeTable { table: Table::new(), key: None } })
+    }
+}
+
+struct SerializeArray(Vec<Value>);
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeTable {
+    table: Table,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeTable {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().ok_or_else(|| Error::new("map value serialized before its key"))?;
+        self.table.insert(key, to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Table(self.table))
+    }
+}
+
+impl ser::SerializeStruct for SerializeTable {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.table.insert(key, to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Table(self.table))
+    }
+}
+
+// Enum variants holding data get wrapped in a table: `{ variant: data }`
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let mut table = Table::new();
+        table.insert(self.variant, ser::SerializeSeq::end(self.inner)?);
+        Ok(Value::Table(table))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeTable> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let mut table = Table::new();
+        table.insert(self.variant, ser::SerializeStruct::end(self.inner)?);
+        Ok(Value::Table(table))
+    }
+}
+
+// Turns map keys into strings, since that's all most formats support
+struct KeySerializer;
+
+macro_rules! key_to_string {
+    ($($method:ident: $type:ty),*) => {
+        $(fn $method(self, value: $type) -> Result<String, Error> { Ok(value.to_string()) })*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    key_to_string!(
+        serialize_bool: bool, serialize_char: char, serialize_str: &str,
+        serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64, serialize_i128: i128,
+        serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64, serialize_u128: u128,
+        serialize_f32: f32, serialize_f64: f64
+    );
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Error> { Err(key_error()) }
+    fn serialize_none(self) -> Result<String, Error> { Err(key_error()) }
+    fn serialize_unit(self) -> Result<String, Error> { Err(key_error()) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> { Err(key_error()) }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> { Err(key_error()) }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> { Err(key_error()) }
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Error> { Err(key_error()) }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> { Err(key_error()) }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Error> { Err(key_error()) }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_error())
+    }
+}
+
+// Gets the string a map key is written as, if it can be one
+#[cfg(any(feature = "ron", feature = "toml"))]
+pub(crate) fn key_string<T: Serialize + ?Sized>(key: &T) -> Option<String> {
+    key.serialize(KeySerializer).ok()
+}
+
+fn key_error() -> Error {
+    Error::new("map keys must be strings, numbers, or booleans")
+}
+
+// ---- Value -> data struct ----
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(value),
+            Value::Integer(value) => {
+                if let Ok(value) = i64::try_from(value) {
+                    visitor.visit_i64(value)
+                } else if let Ok(value) = u64::try_from(value) {
+                    visitor.visit_u64(value)
+                } else {
+                    visitor.visit_i128(value)
+                }
+            }
+            Value::Float(value) => visitor.visit_f64(value),
+            Value::String(value) => visitor.visit_string(value),
+            Value::Array(values) => visitor.visit_seq(ArrayAccess(values.into_iter())),
+            Value::Table(table) => visitor.visit_map(TableAccess { entries: table.into_iter(), value: None }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::Table(table) if table.len() == 1 => {
+                let (variant, value) = table.into_iter().next().ok_or_else(|| Error::new("empty enum table"))?;
+                visitor.visit_enum(EnumAccess { variant, value })
+            }
+            other => Err(de::Error::invalid_type(unexpected(&other), &"an enum variant")),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        match named_struct(self, name, fields) {
+            Ok(inner) => inner.deserialize_any(visitor),
+            Err(value) => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::String(value) => visitor.visit_byte_buf(value.into_bytes()),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+// RON can write the name of a struct in front of it (`Name(key: value)`), which reads like an enum variant: `{ Name: { key: value } }`.
+// Gets the struct out of that, or gives the value back if it's something else
+fn named_struct(value: Value, name: &str, fields: &[&str]) -> Result<Value, Value> {
+    match value {
+        Value::Table(table) if table.len() == 1 && !fields.contains(&name) && matches!(table.get(name), Some(Value::Table(_))) => {
+            table.into_iter().next().map(|(_, inner)| inner).ok_or_else(|| Value::Table(Table::new()))
+        }
+        value => Err(value),
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn unexpected(value: &Value) -> de::Unexpected<'_> {
+    match value {
+        Value::Null => de::Unexpected::Unit,
+        Value::Bool(value) => de::Unexpected::Bool(*value),
+        Value::Integer(value) => match i64::try_from(*value) {
+            Ok(value) => de::Unexpected::Signed(value),
+            Err(_) => de::Unexpected::Other("large integer"),
+        },
+        Value::Float(value) => de::Unexpected::Float(*value),
+        Value::String(value) => de::Unexpected::Str(value),
+        Value::Array(_) => de::Unexpected::Seq,
+        Value::Table(_) => de::Unexpected::Map,
+    }
+}
+
+struct ArrayAccess(std::vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        match self.0.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct TableAccess {
+    entries: std::vec::IntoIter<(String, Value)>,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for TableAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(KeyDeserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self.value.take().ok_or_else(|| Error::new("map value requested before its key"))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = Value;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Value), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Value {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self {
+            Value::Null => Ok(()),
+            other => Err(de::Error::invalid_type(unexpected(&other), &"a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+// Map keys are always strings, but they might need to become numbers (ex: `HashMap<u32, String>`)
+struct KeyDeserializer(String);
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0.parse() {
+                Ok(value) => visitor.$visit(value),
+                Err(_) => visitor.visit_string(self.0),
+            }
+        })*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    parse_key!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16, deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64, deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8, deserialize_u16 => visit_u16, deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64, deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32, deserialize_f64 => visit_f64
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+// ---- Value -> data struct, keeping track of the keys it didn't use ----
+
+// Same as `from_value`, but also returns the dotted paths of the keys the data ignored
+// (ex: a typo'd field name). Arrays are part of the paths by index (ex: `servers.0.hots`)
+pub(crate) fn from_value_tracked<T>(value: Value) -> Result<(T, Vec<String>), Error>
+where
+    T: DeserializeOwned,
+{
+    let unknown = RefCell::new(Vec::new());
+    let data = T::deserialize(Tracked {
+        value,
+        path: String::new(),
+        unknown: &unknown,
+    })?;
+    Ok((data, unknown.into_inner()))
+}
+
+// A value along with where it is in the data
+struct Tracked<'a> {
+    value: Value,
+    path: String,
+    unknown: &'a RefCell<Vec<String>>,
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{path}.{key}"),
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Tracked<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Array(values) => visitor.visit_seq(TrackedArrayAccess {
+                values: values.into_iter().enumerate(),
+                path: self.path,
+                unknown: self.unknown,
+            }),
+            Value::Table(table) => visitor.visit_map(TrackedTableAccess {
+                entries: table.into_iter(),
+                value: None,
+                path: self.path,
+                unknown: self.unknown,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Table(table) if table.len() == 1 => {
+                let (variant, value) = table.into_iter().next().ok_or_else(|| Error::new("empty enum table"))?;
+                let value = Tracked {
+                    value,
+                    path: child_path(&self.path, &variant),
+                    unknown: self.unknown,
+                };
+                visitor.visit_enum(TrackedEnumAccess { variant, value })
+            }
+            value => value.deserialize_enum(name, variants, visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        let value = named_struct(self.value, name, fields).unwrap_or_else(|value| value);
+        Tracked { value, ..self }.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.value.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.value.deserialize_byte_buf(visitor)
+    }
+
+    // Only gets called for values the data has no place for
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if !self.path.is_empty() {
+            self.unknown.borrow_mut().push(self.path);
+        }
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct TrackedArrayAccess<'a> {
+    values: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+    path: String,
+    unknown: &'a RefCell<Vec<String>>,
+}
+
+impl<'de> SeqAccess<'de> for TrackedArrayAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        match self.values.next() {
+            Some((index, value)) => seed
+                .deserialize(Tracked {
+                    value,
+                    path: child_path(&self.path, &index.to_string()),
+                    unknown: self.unknown,
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct TrackedTableAccess<'a> {
+    entries: std::vec::IntoIter<(String, Value)>,
+    value: Option<(String, Value)>,
+    path: String,
+    unknown: &'a RefCell<Vec<String>>,
+}
+
+impl<'de> MapAccess<'de> for TrackedTableAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                let key_value = seed.deserialize(KeyDeserializer(key.clone())).map(Some);
+                self.value = Some((key, value));
+                key_value
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (key, value) = self.value.take().ok_or_else(|| Error::new("map value requested before its key"))?;
+        seed.deserialize(Tracked {
+            value,
+            path: child_path(&self.path, &key),
+            unknown: self.unknown,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct TrackedEnumAccess<'a> {
+    variant: String,
+    value: Tracked<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for TrackedEnumAccess<'a> {
+    type Error = Error;
+    type Variant = Tracked<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Tracked<'a>), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Tracked<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::VariantAccess::unit_variant(self.value)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
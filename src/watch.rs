@@ -0,0 +1,166 @@
+use crate::{error, Config};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+type Callback<D> = Box<dyn Fn(&D, &D) + Send>;
+
+/// Watches a [`Config`]'s file, and reloads its data whenever the file gets modified. <br/>
+/// Only available with the `watch` feature. The data has to implement [`Clone`], since callbacks get a copy of it.
+///
+/// Editors tend to save files in several steps *(truncating then writing, or writing a temporary file
+/// and renaming it over the original)*, so events are collected until the file has been quiet
+/// for a short while before it gets reloaded.
+///
+/// If the modified file can't be parsed, a warning is logged and the previous data is kept.
+///
+/// # Example:
+/// ```no_run
+/// use fast_config::{Config, ConfigWatcher};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize, Clone)]
+/// struct MyData {
+///     pub log_level: String,
+/// }
+///
+/// let config = Config::new("./config/myconfig.json", MyData { log_level: "info".into() }).unwrap();
+/// let watcher = ConfigWatcher::new(config).unwrap();
+/// watcher.on_change(|old, new| {
+///     println!("Log level changed from {} to {}", old.log_level, new.log_level);
+/// });
+///
+/// // The data is behind a lock, since the watcher updates it from another thread
+/// println!("{}", watcher.read().data.log_level);
+/// ```
+pub struct ConfigWatcher<D>
+where
+    for<'a> D: Deserialize<'a> + Serialize,
+{
+    config: Arc<RwLock<Config<D>>>,
+    callbacks: Arc<Mutex<Vec<Callback<D>>>>,
+
+    // Stops watching when dropped
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl<D> ConfigWatcher<D>
+where
+    for<'a> D: Deserialize<'a> + Serialize + Clone + Send + Sync + 'static,
+{
+    /// How long the file has to stay untouched after being modified before it gets reloaded
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+    /// Starts watching a config's file, using [`ConfigWatcher::DEFAULT_DEBOUNCE`].
+    pub fn new(config: Config<D>) -> Result<Self, error::ConfigError> {
+        Self::with_debounce(config, Self::DEFAULT_DEBOUNCE)
+    }
+
+    /// Starts watching a config's file.
+    ///
+    /// - `debounce`: How long the file has to stay untouched after being modified before it gets reloaded.
+    pub fn with_debounce(config: Config<D>, debounce: Duration) -> Result<Self, error::ConfigError> {
+        // Watching the directory instead of the file itself,
+        // since the file gets replaced when it's saved atomically
+        let file_name = config.path.file_name().map(|name| name.to_os_string());
+        let dir = match config.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        std::fs::create_dir_all(&dir)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            // Reading the file (which the watcher itself does) shouldn't count as a change
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            if event.paths.iter().any(|path| path.file_name() == file_name.as_deref()) {
+                let _ = sender.send(());
+            }
+        })
+        .map_err(to_io_error)?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive).map_err(to_io_error)?;
+
+        let config = Arc::new(RwLock::new(config));
+        let callbacks: Arc<Mutex<Vec<Callback<D>>>> = Arc::new(Mutex::new(Vec::new()));
+
+        // Reloading in the background.
+        // The thread stops once the watcher (and with it, the sender) is dropped
+        let thread_config = Arc::clone(&config);
+        let thread_callbacks = Arc::clone(&callbacks);
+        std::thread::spawn(move || {
+            while receiver.recv().is_ok() {
+                // Waiting until the editor is done writing
+                loop {
+                    match receiver.recv_timeout(debounce) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+
+                let mut config = thread_config.write().unwrap_or_else(PoisonError::into_inner);
+                let (old, new) = match config.reload_data() {
+                    // Copying the new data, so that the callbacks run without the config locked
+                    Ok((old, true)) => (old, config.data.clone()),
+                    Ok((_, false)) => continue,
+                    Err(e) => {
+                        log::warn!("Failed to reload \"{}\", keeping the previous data: {e}", config.path.display());
+                        continue;
+                    }
+                };
+                drop(config);
+
+                let callbacks = thread_callbacks.lock().unwrap_or_else(PoisonError::into_inner);
+                for callback in callbacks.iter() {
+                    callback(&old, &new);
+                }
+            }
+        });
+
+        Ok(Self {
+            config,
+            callbacks,
+            _watcher: watcher,
+        })
+    }
+
+    /// Adds a callback that gets called with the old and the new data every time the file changes.
+    ///
+    /// Callbacks run on the watcher's thread without the config locked, so they can [`read`](ConfigWatcher::read),
+    /// [`write`](ConfigWatcher::write) or save it. They can't add more callbacks however, since the list of callbacks is locked.
+    pub fn on_change(&self, callback: impl Fn(&D, &D) + Send + 'static) {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(callback));
+    }
+
+    /// Locks the config for reading.
+    pub fn read(&self) -> RwLockReadGuard<'_, Config<D>> {
+        self.config.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the config for writing *(ex: to change its data and [`Config::save`] it)*.
+    pub fn write(&self) -> RwLockWriteGuard<'_, Config<D>> {
+        self.config.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Gets the shared config, so that it can be handed to other threads.
+    pub fn config(&self) -> Arc<RwLock<Config<D>>> {
+        Arc::clone(&self.config)
+    }
+}
+
+fn to_io_error(error: notify::Error) -> error::ConfigError {
+    match error.kind {
+        notify::ErrorKind::Io(error) => error::ConfigError::IoError(error),
+        kind => error::ConfigError::IoError(std::io::Error::other(format!("{kind:?}"))),
+    }
+}